@@ -0,0 +1,180 @@
+use regex::Regex;
+
+use crate::spec::{Device,Section,Generated,ConfItem,ItemKind,Choice,Signature,Release,Localized};
+
+// Parses the self-description printed by firmwares that know the `spec`
+// command. One line per section or item, fields separated by '|':
+//
+//   [=] <section name>|<help>
+//   [<id>] int|<caption>|<default>|<min>|<max>
+//   [<id>] hex|<caption>|<default>|<bytes>|lsb or msb
+//   [<id>] text|<caption>|<default>|<max length>
+//   [<id>] choice|<caption>|<default>|<val>=<desc>,<val>=<desc>...
+//   [<id>] check|<caption>|<0 or 1>
+//
+// Items before the first section go to a General one. Other lines (banner,
+// [OK]) are ignored.
+pub fn parse(sig: &Signature, lines: &[String]) -> Result<Device,String> {
+    let re=Regex::new(r"^\[([^\]]+)\]\s*(.*)$").unwrap();
+    let mut sections: Vec<Section>=vec!();
+    for l in lines {
+        let cap=match re.captures(l) {
+            Some(c) if &c[1] != "OK" => c,
+            _ => continue,
+        };
+        let f: Vec<&str>=cap[2].split('|').map(str::trim).collect();
+        if &cap[1] == "=" {
+            sections.push(Section { name: f[0].into(), help: (*f.get(1).unwrap_or(&"")).into(), items: vec!(), generated: None });
+            continue;
+        }
+        let item=parse_item(&cap[1],&f).map_err( |e| format!("{}: {}",l,e) )?;
+        if sections.is_empty() {
+            sections.push(Section { name: Localized::default(), help: Localized::default(), items: vec!(), generated: Some(Generated::General) });
+        }
+        sections.last_mut().unwrap().items.push(item);
+    }
+    sections.retain( |s| !s.items.is_empty() );
+    if sections.is_empty() {
+        return Err("the device printed no parameter description".into());
+    }
+    Ok(Device {
+        signature: sig.clone(),
+        title: sig.to_string().into(),
+        serial: None,
+        release: Release::default(),
+        sections,
+        described: true,
+    })
+}
+
+fn field<T: std::str::FromStr>(f: &[&str],n: usize,what: &str) -> Result<T,String> {
+    f.get(n).and_then( |v| v.parse().ok() ).ok_or_else( || format!("missing or invalid {}",what) )
+}
+
+fn parse_item(id: &str,f: &[&str]) -> Result<ConfItem,String> {
+    let id=id.to_string();
+    let caption: Localized=f.get(1).map( |c| (*c).into() ).ok_or("missing caption")?;
+    let kind=match f[0] {
+        "int" => ItemKind::Int { id, caption, val: field(f,2,"default")?, vmin: field(f,3,"minimum")?, vmax: field(f,4,"maximum")? },
+        "hex" => {
+            // The default is printed like `show` does, in device byte order
+            let lsb=f.get(4) == Some(&"lsb");
+            let raw: String=field(f,2,"default")?;
+            ItemKind::Hex { id, caption, val: if lsb { ConfItem::invert(&raw) } else { raw }, maxlen: field(f,3,"size")?, lsb }
+        },
+        "text" => ItemKind::Text { id, caption, val: f.get(2).unwrap_or(&"").to_string(), maxlen: field(f,3,"length")? },
+        "check" => ItemKind::Check { id, caption, val: field::<i32>(f,2,"default")? != 0 },
+        "choice" => {
+            let def: i32=field(f,2,"default")?;
+            let values=f.get(3).unwrap_or(&"").split(',').filter( |o| !o.is_empty() ).map( |o| {
+                let (v,d)=o.split_once('=').ok_or_else( || format!("'{}' is not <value>=<text>",o) )?;
+                Ok(Choice { val: v.trim().parse().map_err( |_| format!("'{}' is not a number",v) )?, desc: d.trim().into() })
+            }).collect::<Result<Vec<Choice>,String>>()?;
+            let val=values.iter().position( |o| o.val == def ).ok_or("default is not one of the choices")?;
+            ItemKind::Choice { id, caption, val, values }
+        },
+        k => return Err(format!("unknown kind '{}'",k)),
+    };
+    Ok(ConfItem::from(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::Value;
+
+    fn sig() -> Signature {
+        Signature::new("BM","24M","9")
+    }
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map( |l| l.to_string() ).collect()
+    }
+
+    fn item(line: &str) -> Result<ConfItem,String> {
+        let dev=parse(&sig(),&lines(&[line]))?;
+        Ok(dev.sections[0].items[0].clone())
+    }
+
+    #[test]
+    fn sections_and_items() {
+        let dev=parse(&sig(),&lines(&[
+            "Boros Met 24 [BM<24M>V9]",
+            "[id] int|Device ID|1|0|255",
+            "[=] Radio|Settings of the nrf24l01",
+            "[cha] int|Channel|76|0|125",
+            "[=] Empty|Dropped, it has no items",
+            "[OK]",
+        ])).unwrap();
+        assert_eq!(dev.signature,sig());
+        let names: Vec<&str>=dev.sections.iter().map( |s| s.name.en() ).collect();
+        assert_eq!(names,vec!("","Radio"));
+        assert_eq!(dev.sections[0].generated,Some(Generated::General));
+        assert_eq!(dev.sections[1].generated,None);
+        assert_eq!(dev.sections[1].help.en(),"Settings of the nrf24l01");
+        assert!(dev.find("cha").is_some());
+    }
+
+    #[test]
+    fn int() {
+        let i=item("[cha] int|Channel|76|0|125").unwrap();
+        assert_eq!(i.caption_en(),"Channel");
+        assert_eq!(*i.value(),Value::Int(76));
+        assert!(i.parse("126").is_err());
+    }
+
+    #[test]
+    fn hex() {
+        let i=item("[pipe] hex|Pipe|EEDDCCBBAA|5|lsb").unwrap();
+        assert_eq!(*i.value(),Value::Hex("AABBCCDDEE".into()));
+        assert_eq!(i.raw(),"EEDDCCBBAA");
+        let i=item("[pipe] hex|Pipe|EEDDCCBBAA|5|msb").unwrap();
+        assert_eq!(*i.value(),Value::Hex("EEDDCCBBAA".into()));
+    }
+
+    #[test]
+    fn text() {
+        let i=item("[tpl] text|Template|%Id,%Td|32").unwrap();
+        assert_eq!(i.raw(),"%Id,%Td");
+        let i=item("[tpl] text|Template||32").unwrap();
+        assert_eq!(i.raw(),"");
+    }
+
+    #[test]
+    fn choice() {
+        let i=item("[rate] choice|Data rate|1|0=1 Mbps,1=2 Mbps,2=250 kbps").unwrap();
+        assert_eq!(*i.value(),Value::Choice(1));
+        assert_eq!(i.describe("2"),"250 kbps");
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(*item("[led] check|Enable led|1").unwrap().value(),Value::Check(true));
+        assert_eq!(*item("[led] check|Enable led|0").unwrap().value(),Value::Check(false));
+    }
+
+    #[test]
+    fn malformed_items() {
+        for l in &[
+            "[cha] int|Channel|76|0",
+            "[cha] int|Channel|x|0|125",
+            "[cha] int",
+            "[pipe] hex|Pipe|EEDD",
+            "[tpl] text|Template|abc",
+            "[rate] choice|Data rate|5|0=1 Mbps,1=2 Mbps",
+            "[rate] choice|Data rate|0|0:1 Mbps",
+            "[rate] choice|Data rate|0|x=1 Mbps",
+            "[led] check|Enable led|on",
+            "[led] toggle|Enable led|1",
+        ] {
+            let e=item(l).err().unwrap_or_else( || panic!("'{}' was accepted",l) );
+            assert!(e.starts_with(l),"{}",e);
+        }
+    }
+
+    #[test]
+    fn nothing_described() {
+        assert!(parse(&sig(),&lines(&["unknown command","[OK]"])).is_err());
+        assert!(parse(&sig(),&lines(&["[=] Radio|No items"])).is_err());
+    }
+}
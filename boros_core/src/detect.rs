@@ -0,0 +1,67 @@
+use serialport::{available_ports,SerialPortInfo,SerialPortType};
+use std::thread;
+
+use crate::spec::Signature;
+use crate::ser::{BorosSerial,ConnectMode};
+use crate::settings::SerialSettings;
+
+#[derive(Default,Clone,Copy)]
+pub struct UsbFilter {
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+}
+
+impl UsbFilter {
+    pub fn is_set(&self) -> bool {
+        self.vid.is_some() || self.pid.is_some()
+    }
+
+    fn accepts(&self, p: &SerialPortInfo) -> bool {
+        match &p.port_type {
+            SerialPortType::UsbPort(u) => self.vid.map_or(true, |v| v==u.vid) && self.pid.map_or(true, |v| v==u.pid),
+            _ => !self.is_set(),
+        }
+    }
+}
+
+pub struct Detected {
+    pub port: String,
+    pub signature: Signature,
+}
+
+// Ports worth probing: USB adapters first, then the rest unless a VID/PID filter is given
+pub fn candidates(filter: &UsbFilter) -> Vec<SerialPortInfo> {
+    let mut ports: Vec<SerialPortInfo>=available_ports().unwrap_or_default().into_iter()
+                                        .filter( |p| filter.accepts(p) ).collect();
+    ports.sort_by_key( |p| match p.port_type { SerialPortType::UsbPort(_) => 0, _ => 1 } );
+    ports
+}
+
+// Tries each line setting in turn until the device answers `ver`
+pub fn probe(port: &str, lines: &[SerialSettings], mode: ConnectMode) -> Option<Signature> {
+    for line in lines {
+        if let Ok(p)=line.open(port) {
+            let mut ser=BorosSerial::with_settings(Box::new(p),*line);
+            if ser.open(mode) {
+                if let Ok(sig)=ser.get_signature() {
+                    return Some(sig);
+                }
+            }
+        }
+    }
+    None
+}
+
+// Probes every candidate in parallel and returns the ports that answered `ver`
+pub fn detect(filter: &UsbFilter, lines: &[SerialSettings], mode: ConnectMode) -> Vec<Detected> {
+    let workers: Vec<_>=candidates(filter).into_iter().map( |p| {
+        let lines=lines.to_vec();
+        thread::spawn(move || probe(&p.port_name,&lines,mode).map( |s| Detected { port: p.port_name, signature: s } ))
+    }).collect();
+    workers.into_iter().filter_map( |w| w.join().ok().flatten() ).collect()
+}
+
+pub fn parse_hex_id(s: &str) -> Result<u16,String> {
+    let t=s.trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(t,16).map_err( |_| format!("'{}' is not a valid USB id",s) )
+}
@@ -0,0 +1,158 @@
+// This file is generated with to embeed spec yml into static variable
+pub const yml : &'static str = r#"
+---
+# Config changes between firmware versions of a product/model, applied in order
+# when a config read from an older device or file is loaded into a newer editor:
+#   - product: BM
+#     model: 24M
+#     from: 4
+#     to: 5
+#     rules:
+#       - Rename: { from: old, to: new }
+#       - Remove: { id: old }
+#       - Map:    { id: mode, values: { "2": "1" } }
+#       - Scale:  { id: repo, factor: 8, offset: 0 }
+#       - Set:    { id: new, value: "0" }
+migrations: []
+spec:
+    - signature: { product: BM , model: 24M  , version: 4 }
+      # caption, help, desc, name, title and changelog take a plain text or
+      # one per language: { en: ..., es: ..., de: ... }. English is the fallback.
+      title:
+        en: Boros Met 24 with plain and mesh protocol v4
+        es: Boros Met 24 con protocolo plain y mesh v4
+        de: Boros Met 24 mit Plain- und Mesh-Protokoll v4
+      serial: { baud: 9600, reset: Dtr, reset_pulse_ms: 100, boot_delay_ms: 1500 }
+      # Release notes of this firmware version
+      release:
+        changelog:
+          en: Plain and mesh nrf24l01 protocols.
+          es: Protocolos nrf24l01 plain y mesh.
+          de: Plain- und Mesh-Protokolle für nrf24l01.
+        min_gui: "0.1.0"
+      sections:
+        - &GeneralMet
+          name: { en: General settings, es: Ajustes generales, de: Allgemeine Einstellungen }
+          help:
+            en: |
+              This tab configures general behavior parameters:
+                - Device ID: 16bit identification of the sensor.
+                - Enable led: Blink the led during notification. Set it on for visual feedback.
+                - Enable wake interrupt: Enable it if the sensor uses an external interrupt to start notification.
+                - Notification interval: Notification period in 8s steps. e.g: 15 = 2 minutes
+                - Payload template: template to build the payload (see docs for syntax)
+            es: |
+              Esta pestaña configura el comportamiento general:
+                - ID del dispositivo: identificación de 16 bits del sensor.
+                - Activar led: parpadea durante la notificación. Actívelo para tener una señal visual.
+                - Activar interrupción de despertar: actívela si el sensor usa una interrupción externa para iniciar la notificación.
+                - Intervalo de notificación: periodo de notificación en pasos de 8s. p. ej.: 15 = 2 minutos
+                - Plantilla de datos: plantilla para construir los datos enviados (ver la documentación)
+            de: |
+              Dieser Reiter stellt das allgemeine Verhalten ein:
+                - Geräte-ID: 16-Bit-Kennung des Sensors.
+                - LED aktivieren: LED blinkt bei jeder Meldung. Für eine sichtbare Rückmeldung einschalten.
+                - Weck-Interrupt aktivieren: einschalten, wenn der Sensor Meldungen über einen externen Interrupt startet.
+                - Meldeintervall: Meldeperiode in Schritten von 8s. z. B.: 15 = 2 Minuten
+                - Nutzdatenvorlage: Vorlage für die gesendeten Daten (Syntax siehe Dokumentation)
+          items:
+            - Int:   { id: id  , caption: { en: Device ID, es: ID del dispositivo, de: Geräte-ID }, val: 1 , vmin: 0, vmax: 65536 }
+            - Check: { id: led , caption: { en: Enable led, es: Activar led, de: LED aktivieren }, val: false }
+            - Check: { id: enint, caption: { en: Enable wake interrupt, es: Activar interrupción de despertar, de: Weck-Interrupt aktivieren }, val: false }
+            - Int:   { id: repo, caption: { en: Notification interval, es: Intervalo de notificación, de: Meldeintervall }, val: 15, vmin: 0, vmax: 65536 }
+            - Text:  { id: tpl, caption: { en: Payload template, es: Plantilla de datos, de: Nutzdatenvorlage }, val: "%Id,%Td,%Hd" , maxlen: 102 }
+        - &RF24Config
+          name: { en: Radio configuration, es: Configuración de radio, de: Funkeinstellungen }
+          help:
+            en: |
+              This tab configures the nrf24l01 parameters:
+                - RF24 mode: Plain or Mesh mode
+                - Tx power: Radio output level (MIN,LOW,HIGH,MAX)
+                - Enable Lna: Enable amplification if the module supports it.
+                - Channel: radio channel to use
+                - Data Rate: 250Kbps, 1Mbps, 2Mbps
+            es: |
+              Esta pestaña configura los parámetros del nrf24l01:
+                - Modo RF24: modo Plain o Mesh
+                - Potencia de emisión: nivel de salida de la radio (MIN,LOW,HIGH,MAX)
+                - Activar LNA: activa la amplificación si el módulo la admite.
+                - Canal: canal de radio a usar
+                - Velocidad de datos: 250Kbps, 1Mbps, 2Mbps
+            de: |
+              Dieser Reiter stellt die Parameter des nrf24l01 ein:
+                - RF24-Modus: Plain- oder Mesh-Modus
+                - Sendeleistung: Ausgangspegel des Funkmoduls (MIN,LOW,HIGH,MAX)
+                - LNA aktivieren: Verstärkung einschalten, wenn das Modul sie unterstützt.
+                - Kanal: verwendeter Funkkanal
+                - Datenrate: 250Kbps, 1Mbps, 2Mbps
+          items:
+              - Choice: { id: mode, caption: { en: RF24 mode, es: Modo RF24, de: RF24-Modus }, val: 0 , values: [ {val: 0 , desc: Plain }, { val : 1, desc: Mesh} ] }
+              - Choice: { id: txp , caption: { en: Tx power, es: Potencia de emisión, de: Sendeleistung }, val: 1 , values: [ {val: 0 , desc: { en: Min, es: Mínima, de: Minimal } }, {val: 1, desc: { en: Low, es: Baja, de: Niedrig } } , {val: 2, desc: { en: High, es: Alta, de: Hoch } } , {val: 3, desc: { en: Max, es: Máxima, de: Maximal } } ] }
+              - Check:  { id: lna , caption: { en: Enable Lna, es: Activar LNA, de: LNA aktivieren }, val: false }
+              - Int:    { id: cha,  caption: { en: Channel, es: Canal, de: Kanal }, val: 76, vmin: 1, vmax: 126 }
+              - Choice: { id: rate, caption: { en: Data rate, es: Velocidad de datos, de: Datenrate }, val: 1, values: [ {val: 0, desc: 250Kbps}, {val: 1 , desc: 1Mbps },{val: 2, desc: 2Mbps } ] }
+        - &RF24Plain
+          name: { en: Plain mode, es: Modo plain, de: Plain-Modus }
+          help:
+            en: |
+              Parameters on this tab apply only if 'plain' mode is selected:
+                - Pipe address size: Size of the destination pipe address.
+                - Notification pipe: Hex address to notify sensors readings.
+                - Payload size: Size of the fixed size payload. If 0 the dynamic payload feature is enabled.
+                - CRC: Enable CRC check mode.
+                - Enable Ack: Enable the auto ack protocol of the nrf24l01 hardware.
+                - Retries: 0-15 retry attempts (if ack is enabled)
+                - Retry delay: 0-15 Delay (n+1)*250us to wait before retry.
+            es: |
+              Los parámetros de esta pestaña solo se aplican en modo 'plain':
+                - Tamaño de dirección del pipe: tamaño de la dirección del pipe de destino.
+                - Pipe de notificación: dirección hexadecimal a la que se envían las lecturas.
+                - Tamaño de datos: tamaño fijo de los datos enviados. Con 0 se activan los datos de tamaño dinámico.
+                - CRC: modo de comprobación CRC.
+                - Activar Ack: activa el protocolo de auto ack del hardware nrf24l01.
+                - Reintentos: 0-15 intentos (si ack está activado)
+                - Espera entre reintentos: 0-15, espera de (n+1)*250us antes de reintentar.
+            de: |
+              Die Parameter dieses Reiters gelten nur im Modus 'plain':
+                - Größe der Pipe-Adresse: Größe der Adresse der Ziel-Pipe.
+                - Melde-Pipe: Hex-Adresse, an die die Messwerte gemeldet werden.
+                - Nutzdatengröße: feste Größe der Nutzdaten. Bei 0 werden dynamische Nutzdaten aktiviert.
+                - CRC: CRC-Prüfmodus.
+                - Ack aktivieren: Auto-Ack-Protokoll der nrf24l01-Hardware einschalten.
+                - Wiederholungen: 0-15 Versuche (wenn Ack aktiviert ist)
+                - Wiederholungspause: 0-15, Wartezeit (n+1)*250us vor jeder Wiederholung.
+          items:
+            - Int: { id: psz, caption: { en: Pipe address size, es: Tamaño de dirección del pipe, de: Größe der Pipe-Adresse }, val: 5, vmin: 3, vmax: 5}
+            - Hex: { id: pipe, caption: { en: Notification pipe, es: Pipe de notificación, de: Melde-Pipe }, val: AABBCCDDEE , maxlen: 5 , lsb: true }
+            - Int: { id: dsz, caption: { en: Payload size, es: Tamaño de datos, de: Nutzdatengröße }, val: 32 , vmin: 0, vmax: 32 }
+            - Choice: { id: crc, caption: CRC, val: 2 , values: [ {val: 0, desc: { en: Disabled, es: Desactivado, de: Deaktiviert } }, {val: 1, desc: 8bit}, {val: 2, desc: 16bit} ] }
+            - Check: { id: ack, caption: { en: Enable Ack, es: Activar Ack, de: Ack aktivieren }, val: true }
+            - Int: { id: retr , caption: { en: Retries, es: Reintentos, de: Wiederholungen }, val: 8, vmin: 0, vmax: 15 }
+            - Int: { id: retd , caption: { en: Retry delay, es: Espera entre reintentos, de: Wiederholungspause }, val: 15, vmin: 0, vmax: 15 }
+        - &RF24Mesh
+          name: { en: Mesh mode, es: Modo mesh, de: Mesh-Modus }
+          help:
+            en: |
+              Parameters on this tab apply only if 'mesh' mode is selected:
+                - Node ID: Mesh node id of the sensor
+                - Frame type: Mesh frame type id (byte)
+                - Notification node ID: Node in the mesh to notify sensor information.
+                - Force mesh renew: Force renew mesh network address in each notification.
+            es: |
+              Los parámetros de esta pestaña solo se aplican en modo 'mesh':
+                - ID de nodo: id de nodo mesh del sensor
+                - Tipo de trama: id del tipo de trama mesh (byte)
+                - ID del nodo de notificación: nodo de la red mesh al que se envía la información del sensor.
+                - Forzar renovación mesh: renueva la dirección de red mesh en cada notificación.
+            de: |
+              Die Parameter dieses Reiters gelten nur im Modus 'mesh':
+                - Knoten-ID: Mesh-Knoten-ID des Sensors
+                - Rahmentyp: ID des Mesh-Rahmentyps (Byte)
+                - Melde-Knoten-ID: Knoten im Mesh, an den die Sensordaten gemeldet werden.
+                - Mesh-Erneuerung erzwingen: Mesh-Netzadresse bei jeder Meldung erneuern.
+          items:
+              - Int: { id: mnid , caption: { en: Node ID, es: ID de nodo, de: Knoten-ID }, val: 10, vmin: 1, vmax: 255 }
+              - Int: { id: mfid , caption: { en: Frame type, es: Tipo de trama, de: Rahmentyp }, val: 30, vmin: 0, vmax: 255 }
+              - Int: { id: mdst , caption: { en: Notification node ID, es: ID del nodo de notificación, de: Melde-Knoten-ID }, val: 0, vmin: 0, vmax: 255 }
+              - Check: { id: mfor, caption: { en: Force mesh renew, es: Forzar renovación mesh, de: Mesh-Erneuerung erzwingen }, val: false }
+"#;
@@ -0,0 +1,120 @@
+use crate::spec::{Device,ConfigEntry,ConfItem,ItemKind};
+
+// Differences between the spec of a device and what its firmware reports in
+// `show`. The GUI words the report in its own language.
+#[derive(Default,Debug)]
+pub struct Drift {
+    // Entries the device has and the spec lacks
+    pub unknown: Vec<ConfigEntry>,
+    // (id,caption) of spec items the device did not return
+    pub missing: Vec<(String,String)>,
+    // (id,spec caption,firmware label) when they don't match
+    pub captions: Vec<(String,String,String)>,
+    // (id,raw value,spec limits) for values the spec does not accept
+    pub values: Vec<(String,String,Limits)>,
+    // (id,spec limits,firmware limits) when the firmware describes other limits
+    pub ranges: Vec<(String,Limits,Limits)>,
+}
+
+// Values an item accepts, as the spec or the firmware description sets them
+#[derive(Debug,Clone,PartialEq)]
+pub enum Limits {
+    Int(i32,i32),
+    Hex(usize),
+    Text(usize),
+    // Device values of the choices, sorted
+    Choice(Vec<i32>),
+    Check,
+}
+
+impl Limits {
+    pub fn of(item: &ConfItem) -> Self {
+        match &item.kind {
+            ItemKind::Int { vmin, vmax, .. } => Limits::Int(*vmin,*vmax),
+            ItemKind::Hex { maxlen, .. } => Limits::Hex(*maxlen),
+            ItemKind::Text { maxlen, .. } => Limits::Text(*maxlen),
+            ItemKind::Choice { values, .. } => {
+                let mut v: Vec<i32>=values.iter().map( |o| o.val ).collect();
+                v.sort_unstable();
+                Limits::Choice(v)
+            },
+            ItemKind::Check { .. } => Limits::Check,
+        }
+    }
+}
+
+
+// Captions are compared on letters and digits only, ignoring case
+fn same_caption(a: &str, b: &str) -> bool {
+    let norm=|s: &str| s.chars().filter( |c| c.is_alphanumeric() ).flat_map(char::to_lowercase).collect::<String>();
+    norm(a) == norm(b)
+}
+
+impl Drift {
+    pub fn check(dev: &Device, entries: &[ConfigEntry]) -> Self {
+        let mut d=Drift::default();
+        for e in entries {
+            match dev.find(&e.id) {
+                None => d.unknown.push(e.clone()),
+                Some(item) => {
+                    if !e.label.is_empty() && !same_caption(item.caption_en(),&e.label) {
+                        d.captions.push((e.id.clone(),item.caption_en().into(),e.label.clone()));
+                    }
+                    if !item.validate(&e.raw_value) {
+                        d.values.push((e.id.clone(),e.raw_value.clone(),Limits::of(item)));
+                    }
+                },
+            }
+        }
+        for item in dev.items() {
+            if !entries.iter().any( |e| item.is(&e.id) ) {
+                d.missing.push((item.id().into(),item.caption().into()));
+            }
+        }
+        d
+    }
+
+    // Compares the limits of the spec with the firmware's own description
+    // (`spec`) of the same device
+    pub fn check_ranges(&mut self, dev: &Device, firmware: &Device) {
+        for item in dev.items() {
+            if let Some(fw)=firmware.find(item.id()) {
+                let (spec,desc)=(Limits::of(item),Limits::of(fw));
+                if spec != desc {
+                    self.ranges.push((item.id().into(),spec,desc));
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.missing.is_empty() && self.captions.is_empty() && self.values.is_empty() && self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::describe;
+    use crate::spec::Signature;
+
+    fn device(lines: &[&str]) -> Device {
+        let lines: Vec<String>=lines.iter().map( |l| l.to_string() ).collect();
+        describe::parse(&Signature::new("BM","24M","4"),&lines).unwrap()
+    }
+
+    #[test]
+    fn limits_described_by_the_firmware() {
+        let spec=device(&["[rep] int|Repeat|10|1|60","[nam] text|Name|x|16","[mod] choice|Mode|0|0=Off,1=On","[led] check|Led|0"]);
+        let fw=device(&["[rep] int|Repeat|10|1|120","[nam] text|Name|x|16","[mod] choice|Mode|0|1=On,0=Off,2=Auto","[new] check|New|0"]);
+        let mut d=Drift::default();
+        d.check_ranges(&spec,&fw);
+        assert_eq!(d.ranges.len(),2);
+        assert_eq!(d.ranges[0],("rep".into(),Limits::Int(1,60),Limits::Int(1,120)));
+        assert_eq!(d.ranges[1],("mod".into(),Limits::Choice(vec!(0,1)),Limits::Choice(vec!(0,1,2))));
+        assert!(!d.is_empty());
+        let mut same=Drift::default();
+        same.check_ranges(&spec,&spec);
+        assert!(same.is_empty());
+    }
+}
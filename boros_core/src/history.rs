@@ -0,0 +1,96 @@
+use serde::{Deserialize,Serialize};
+use std::collections::BTreeSet;
+use std::io::{Result,Error,ErrorKind};
+use std::ffi::OsStr;
+use std::path::{Path,PathBuf};
+
+use crate::spec::Signature;
+use crate::snapshot::ConfigFile;
+use crate::transcript::timestamp;
+use crate::paths::data_dir;
+
+// One configuration seen on a device. The file is also a valid ConfigFile.
+#[derive(Serialize,Deserialize)]
+pub struct Snapshot {
+    pub time: String,
+    pub kind: String,
+    #[serde(flatten)]
+    pub config: ConfigFile,
+}
+
+pub struct Entry {
+    pub path: PathBuf,
+    pub time: String,
+    pub kind: String,
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"{} ({})",self.time,self.kind)
+    }
+}
+
+// Snapshots are kept in <data dir>/history/<product>-<model>/id-<device id>/,
+// one YAML file per read or write, named after the UTC time.
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn open() -> Self {
+        Self { dir: data_dir().join("history") }
+    }
+
+    fn device_dir(&self, sig: &Signature, values: &[(String,String)]) -> PathBuf {
+        let id=values.iter().find( |v| v.0 == "id" ).map( |v| v.1.as_str() ).unwrap_or("unknown");
+        let clean: String=id.chars().filter( |c| c.is_ascii_alphanumeric() ).collect();
+        self.dir.join(format!("{}-{}",sig.product,sig.model)).join(format!("id-{}",clean))
+    }
+
+    pub fn record(&self, sig: &Signature, values: &[(String,String)], kind: &str) -> Result<PathBuf> {
+        let dir=self.device_dir(sig,values);
+        std::fs::create_dir_all(&dir)?;
+        let time=timestamp();
+        let path=dir.join(format!("{}-{}.yml",time.replace(':',"-"),kind));
+        let snap=Snapshot { time, kind: kind.into(), config: ConfigFile::new(sig.clone(),values.to_vec()) };
+        let text=serde_yaml::to_string(&snap).map_err( |e| Error::new(ErrorKind::InvalidData,e.to_string()) )?;
+        std::fs::write(&path,text)?;
+        Ok(path)
+    }
+
+    // Snapshots of the device owning `values`, oldest first
+    pub fn list(&self, sig: &Signature, values: &[(String,String)]) -> Vec<Entry> {
+        let mut entries: Vec<Entry>=match std::fs::read_dir(self.device_dir(sig,values)) {
+            Ok(rd) => rd.filter_map( |e| e.ok() ).map( |e| e.path() )
+                        .filter( |p| p.extension() == Some(OsStr::new("yml")) )
+                        .filter_map( |p| History::load(&p).ok().map( |s| Entry { path: p, time: s.time, kind: s.kind } ) )
+                        .collect(),
+            Err(_) => vec!(),
+        };
+        entries.sort_by( |a,b| a.time.cmp(&b.time) );
+        entries
+    }
+
+    pub fn load(path: &Path) -> Result<Snapshot> {
+        let text=std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&text).map_err( |e| Error::new(ErrorKind::InvalidData,e.to_string()) )
+    }
+}
+
+// Human readable changes going from `a` to `b`
+pub fn diff(a: &ConfigFile, b: &ConfigFile) -> Vec<String> {
+    let mut out=vec!();
+    if a.signature != b.signature {
+        out.push(format!("signature: {} -> {}",a.signature,b.signature));
+    }
+    let ids: BTreeSet<&String>=a.values.keys().chain(b.values.keys()).collect();
+    for id in ids {
+        match (a.values.get(id),b.values.get(id)) {
+            (Some(x),Some(y)) if x != y => out.push(format!("{}: {} -> {}",id,x,y)),
+            (Some(x),None) => out.push(format!("{}: {} -> (removed)",id,x)),
+            (None,Some(y)) => out.push(format!("{}: (new) -> {}",id,y)),
+            _ => {},
+        }
+    }
+    out
+}
@@ -0,0 +1,86 @@
+use std::io::{Result,Error,ErrorKind};
+use std::path::Path;
+
+// Flash image built from an Intel HEX file. Gaps are filled with 0xFF (erased flash).
+pub struct Image {
+    pub base: usize,
+    pub data: Vec<u8>,
+}
+
+fn invalid(line: usize, msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData,format!("HEX line {}: {}",line,msg))
+}
+
+impl Image {
+    pub fn load(path: &Path,flash_size: usize) -> Result<Self> {
+        Image::parse(&std::fs::read_to_string(path)?,flash_size)
+    }
+
+    // Data past `flash_size` is refused, so extended address records can't
+    // make the image grow without bounds
+    pub fn parse(text: &str,flash_size: usize) -> Result<Self> {
+        let mut chunks: Vec<(usize,Vec<u8>)>=vec!();
+        let mut upper=0usize;
+        let mut eof=false;
+
+        for (n,l) in text.lines().enumerate().map( |(n,l)| (n+1,l.trim()) ) {
+            if l.is_empty() { continue }
+            if !l.starts_with(':') || l.len() < 11 || l.len() % 2 == 0 {
+                return Err(invalid(n,"malformed record"));
+            }
+            let bytes=(1..l.len()).step_by(2)
+                        .map( |i| u8::from_str_radix(&l[i..i+2],16) )
+                        .collect::<std::result::Result<Vec<u8>,_>>()
+                        .map_err( |_| invalid(n,"bad hex digit") )?;
+            let len=bytes[0] as usize;
+            if bytes.len() != len + 5 {
+                return Err(invalid(n,"length mismatch"));
+            }
+            if bytes.iter().fold(0u8, |a,b| a.wrapping_add(*b)) != 0 {
+                return Err(invalid(n,"checksum error"));
+            }
+            let addr=((bytes[1] as usize) << 8) | bytes[2] as usize;
+            let payload=&bytes[4..4+len];
+            match bytes[3] {
+                0x00 if upper + addr + len > flash_size => return Err(invalid(n,&format!("data past the {} bytes of flash",flash_size))),
+                0x00 => chunks.push((upper + addr,payload.to_vec())),
+                0x01 => { eof=true; break },
+                0x02 if len == 2 => upper=(((payload[0] as usize) << 8) | payload[1] as usize) << 4,
+                0x04 if len == 2 => upper=(((payload[0] as usize) << 8) | payload[1] as usize) << 16,
+                0x03 | 0x05 => {},
+                _ => return Err(invalid(n,"unsupported record")),
+            }
+        }
+        if !eof {
+            return Err(Error::new(ErrorKind::UnexpectedEof,"HEX file has no end record"));
+        }
+        let base=chunks.iter().map( |c| c.0 ).min().unwrap_or(0);
+        let end=chunks.iter().map( |c| c.0 + c.1.len() ).max().unwrap_or(0);
+        let mut data=vec![0xFFu8; end - base];
+        for (a,d) in chunks {
+            data[a-base..a-base+d.len()].copy_from_slice(&d);
+        }
+        Ok(Self { base, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaps_are_erased_flash() {
+        let image=Image::parse(":0100100001EE\n:0100120002EB\n:00000001FF\n",1024).unwrap();
+        assert_eq!(image.base,0x10);
+        assert_eq!(image.data,vec!(1,0xFF,2));
+    }
+
+    #[test]
+    fn data_past_the_flash() {
+        // Extended linear address 0x1000_0000, then one byte
+        let err=Image::parse(":020000041000EA\n:0100000001FE\n:00000001FF\n",32*1024).err().unwrap();
+        assert_eq!(err.kind(),ErrorKind::InvalidData);
+        assert!(Image::parse(":0100000001FE\n:00000001FF\n",1).is_ok());
+        assert!(Image::parse(":0100010001FD\n:00000001FF\n",1).is_err());
+    }
+}
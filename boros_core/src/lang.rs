@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicUsize,Ordering};
+
+// Languages of the GUI catalog and of localized spec texts, English first as
+// the fallback for anything not translated.
+pub const LANGUAGES: &[(&str,&str)] = &[("en","English"),("es","Español"),("de","Deutsch")];
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn current() -> &'static str {
+    LANGUAGES[CURRENT.load(Ordering::Relaxed)].0
+}
+
+// Supported language of a locale name like "es", "de-AT" or "es_ES.UTF-8"
+pub fn parse(locale: &str) -> Option<&'static str> {
+    let code=locale.split(['_','-','.','@']).next()?.to_lowercase();
+    LANGUAGES.iter().find( |(c,_)| *c==code ).map( |(c,_)| *c )
+}
+
+// Language of the environment. The first of LC_ALL, LC_MESSAGES and LANG
+// that is set decides, as for other programs.
+pub fn from_locale() -> Option<&'static str> {
+    ["LC_ALL","LC_MESSAGES","LANG"].iter()
+        .filter_map( |v| std::env::var(v).ok() )
+        .find( |v| !v.is_empty() )
+        .and_then( |v| parse(&v) )
+}
+
+// Uses `code` (from the preferences), else the locale, else English
+pub fn select(code: Option<&str>) -> &'static str {
+    let lang=code.and_then(parse).or_else(from_locale).unwrap_or("en");
+    CURRENT.store(LANGUAGES.iter().position( |(c,_)| *c==lang ).unwrap_or(0),Ordering::Relaxed);
+    lang
+}
@@ -0,0 +1,21 @@
+// Boros sensor configuration without any GUI: spec model, serial protocol
+// client, firmware upload and local configuration stores.
+
+pub mod lang;
+pub mod spec;
+pub mod describe;
+pub mod drift;
+pub mod ser;
+pub mod worker;
+pub mod settings;
+pub mod detect;
+pub mod transcript;
+pub mod replay;
+pub mod ihex;
+pub mod stk500;
+pub mod migrate;
+pub mod snapshot;
+pub mod history;
+pub mod profiles;
+pub mod paths;
+mod devices;
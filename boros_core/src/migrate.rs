@@ -0,0 +1,143 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// Change of a configuration item between two firmware versions
+#[derive(Deserialize,Clone,Debug)]
+pub enum Rule {
+    Rename { from: String, to: String },
+    Remove { id: String },
+    // Replaces raw values, e.g. renumbered choices. Unlisted values are kept.
+    Map { id: String, values: BTreeMap<String,String> },
+    // Numeric change of unit: new = round(old * factor + offset)
+    Scale { id: String, #[serde(default="one")] factor: f64, #[serde(default)] offset: f64 },
+    // Forces a value, added if the item was not present
+    Set { id: String, value: String },
+}
+
+fn one() -> f64 { 1.0 }
+
+// Rules to go from firmware version `from` to `to` of a product/model
+#[derive(Deserialize,Clone,Debug)]
+pub struct Migration {
+    pub product: String,
+    pub model: String,
+    pub from: i32,
+    pub to: i32,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+// What a migration changed, as "<id>: <old> -> <new>" style entries and
+// dropped ids. The GUI words it in its own language.
+#[derive(Default,Debug)]
+pub struct Report {
+    pub converted: Vec<String>,
+    pub dropped: Vec<String>,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.converted.is_empty() && self.dropped.is_empty()
+    }
+}
+
+// Applies the rules in order to (id,raw value) pairs read from a device or a file
+pub fn apply(rules: &[Rule], values: Vec<(String,String)>, report: &mut Report) -> Vec<(String,String)> {
+    let mut values=values;
+    for r in rules {
+        match r {
+            Rule::Rename { from, to } => {
+                for v in values.iter_mut().filter( |v| v.0 == *from ) {
+                    v.0=to.clone();
+                    report.converted.push(format!("{} -> {}",from,to));
+                }
+            },
+            Rule::Remove { id } => {
+                if values.iter().any( |v| v.0 == *id ) {
+                    values.retain( |v| v.0 != *id );
+                    report.dropped.push(id.clone());
+                }
+            },
+            Rule::Map { id, values: map } => {
+                for v in values.iter_mut().filter( |v| v.0 == *id ) {
+                    if let Some(n)=map.get(&v.1) {
+                        report.converted.push(format!("{}: {} -> {}",id,v.1,n));
+                        v.1=n.clone();
+                    }
+                }
+            },
+            Rule::Scale { id, factor, offset } => {
+                for v in values.iter_mut().filter( |v| v.0 == *id ) {
+                    if let Ok(x)=v.1.trim().parse::<f64>() {
+                        let n=format!("{}",(x * factor + offset).round() as i64);
+                        report.converted.push(format!("{}: {} -> {}",id,v.1,n));
+                        v.1=n;
+                    }
+                }
+                // A value that is not a number can't be rescaled, the new
+                // firmware gets its default instead
+                let before=values.len();
+                values.retain( |v| v.0 != *id || v.1.trim().parse::<f64>().is_ok() );
+                if values.len() < before {
+                    report.dropped.push(id.clone());
+                }
+            },
+            Rule::Set { id, value } => {
+                match values.iter_mut().find( |v| v.0 == *id ) {
+                    Some(v) => v.1=value.clone(),
+                    None => values.push((id.clone(),value.clone())),
+                }
+                report.converted.push(format!("{} = {}",id,value));
+            },
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(v: &[(&str,&str)]) -> Vec<(String,String)> {
+        v.iter().map( |(id,raw)| (id.to_string(),raw.to_string()) ).collect()
+    }
+
+    #[test]
+    fn rules_and_report() {
+        let rules=vec!(
+            Rule::Rename { from: "repo".into(), to: "interval".into() },
+            Rule::Remove { id: "led".into() },
+            Rule::Map { id: "txp".into(), values: vec!(("1".to_string(),"3".to_string())).into_iter().collect() },
+            Rule::Scale { id: "interval".into(), factor: 60.0, offset: 0.0 },
+            Rule::Set { id: "mesh".into(), value: "1".into() },
+        );
+        let mut report=Report::default();
+        let out=apply(&rules,values(&[("repo","15"),("led","1"),("txp","1"),("tpl","%Id")]),&mut report);
+        assert_eq!(out,values(&[("interval","900"),("txp","3"),("tpl","%Id"),("mesh","1")]));
+        assert_eq!(report.converted,vec!("repo -> interval","txp: 1 -> 3","interval: 15 -> 900","mesh = 1"));
+        assert_eq!(report.dropped,vec!("led"));
+    }
+
+    #[test]
+    fn absent_items_are_untouched() {
+        let rules=vec!(
+            Rule::Rename { from: "repo".into(), to: "interval".into() },
+            Rule::Remove { id: "led".into() },
+            Rule::Map { id: "txp".into(), values: vec!(("1".to_string(),"3".to_string())).into_iter().collect() },
+            Rule::Scale { id: "repo".into(), factor: 2.0, offset: 1.0 },
+        );
+        let mut report=Report::default();
+        let out=apply(&rules,values(&[("txp","0"),("tpl","%Id")]),&mut report);
+        assert_eq!(out,values(&[("txp","0"),("tpl","%Id")]));
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn scale_drops_values_that_are_not_numbers() {
+        let mut report=Report::default();
+        let out=apply(&[Rule::Scale { id: "pipe".into(), factor: 2.0, offset: 0.0 }],values(&[("pipe","AABBCCDDEE"),("repo","15")]),&mut report);
+        assert_eq!(out,values(&[("repo","15")]));
+        assert!(report.converted.is_empty());
+        assert_eq!(report.dropped,vec!("pipe"));
+    }
+}
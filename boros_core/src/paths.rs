@@ -0,0 +1,22 @@
+use std::env::var_os;
+use std::path::PathBuf;
+
+const APP: &str = "boros_gui";
+
+fn home() -> PathBuf {
+    var_os("HOME").or_else( || var_os("USERPROFILE") ).map(PathBuf::from).unwrap_or_else( || PathBuf::from(".") )
+}
+
+// Per user data directory ($XDG_DATA_HOME, %APPDATA% or ~/.local/share)
+pub fn data_dir() -> PathBuf {
+    let base=var_os("XDG_DATA_HOME").or_else( || var_os("APPDATA") ).map(PathBuf::from)
+                .unwrap_or_else( || home().join(".local").join("share") );
+    base.join(APP)
+}
+
+// Per user configuration directory ($XDG_CONFIG_HOME, %APPDATA% or ~/.config)
+pub fn config_dir() -> PathBuf {
+    let base=var_os("XDG_CONFIG_HOME").or_else( || var_os("APPDATA") ).map(PathBuf::from)
+                .unwrap_or_else( || home().join(".config") );
+    base.join(APP)
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize,Serialize};
+use std::ffi::OsStr;
+use std::io::{Result,Error,ErrorKind};
+use std::path::{Path,PathBuf};
+
+use crate::spec::Signature;
+use crate::snapshot::ConfigFile;
+use crate::paths::config_dir;
+
+// Named partial configuration. Applying it only touches the listed items.
+#[derive(Serialize,Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(flatten)]
+    pub config: ConfigFile,
+}
+
+fn invalid(e: serde_yaml::Error) -> Error {
+    Error::new(ErrorKind::InvalidData,e.to_string())
+}
+
+// Profiles live in <config dir>/profiles, one YAML file each
+pub struct Profiles {
+    dir: PathBuf,
+}
+
+impl Profiles {
+    pub fn open() -> Self {
+        Self { dir: config_dir().join("profiles") }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        let file: String=name.chars().map( |c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' } ).collect();
+        self.dir.join(format!("{}.yml",file))
+    }
+
+    fn files(&self) -> Vec<(PathBuf,Profile)> {
+        let mut all: Vec<(PathBuf,Profile)>=match std::fs::read_dir(&self.dir) {
+            Ok(rd) => rd.filter_map( |e| e.ok() ).map( |e| e.path() )
+                        .filter( |p| p.extension() == Some(OsStr::new("yml")) )
+                        .filter_map( |p| Profiles::read(&p).ok().map( |f| (p,f) ) )
+                        .collect(),
+            Err(_) => vec!(),
+        };
+        all.sort_by( |a,b| a.1.name.cmp(&b.1.name) );
+        all
+    }
+
+    fn find(&self, name: &str) -> Result<(PathBuf,Profile)> {
+        self.files().into_iter().find( |f| f.1.name == name )
+            .ok_or_else( || Error::new(ErrorKind::NotFound,format!("no profile named '{}'",name)) )
+    }
+
+    pub fn list(&self) -> Vec<Profile> {
+        self.files().into_iter().map( |f| f.1 ).collect()
+    }
+
+    // Profiles usable on a device: same model, same or older firmware
+    pub fn for_device(&self, sig: &Signature) -> Vec<Profile> {
+        self.list().into_iter().filter( |p| p.config.signature.same_model(sig) && p.config.signature.version <= sig.version ).collect()
+    }
+
+    pub fn load(&self, name: &str) -> Result<Profile> {
+        self.find(name).map( |f| f.1 )
+    }
+
+    // Replaces the profile with the same name, if any
+    pub fn save(&self, p: &Profile) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path=self.find(&p.name).map( |f| f.0 ).unwrap_or_else( |_| self.path(&p.name) );
+        std::fs::write(&path,serde_yaml::to_string(p).map_err(invalid)?)?;
+        Ok(path)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        std::fs::remove_file(self.find(name)?.0)
+    }
+
+    fn read(path: &Path) -> Result<Profile> {
+        serde_yaml::from_str(&std::fs::read_to_string(path)?).map_err(invalid)
+    }
+
+    // Stores a saved configuration or a profile file. The device `id` is never part of a profile.
+    pub fn import(&self, path: &Path) -> Result<Profile> {
+        let mut p=match Profiles::read(path) {
+            Ok(p) => p,
+            Err(_) => {
+                let name=path.file_stem().map( |s| s.to_string_lossy().into_owned() ).unwrap_or_default();
+                Profile { name, config: ConfigFile::load(path)? }
+            }
+        };
+        p.config.values.remove("id");
+        self.save(&p)?;
+        Ok(p)
+    }
+}
+
+impl std::fmt::Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f,"{} ({})",self.name,self.config.signature)?;
+        for (id,v) in &self.config.values {
+            writeln!(f,"  {} = {}",id,v)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+use std::io::{Read,Write,Result,Error,ErrorKind};
+use std::path::Path;
+use std::sync::{Arc,Mutex};
+use std::time::Duration;
+
+use crate::spec::{Root,Device};
+use crate::ser::{BorosSerial,Transport};
+use crate::settings::Timeouts;
+use crate::drift::Drift;
+use crate::transcript::{self,escape,TX,RX};
+
+enum Entry {
+    Sent(Vec<u8>),
+    Received(Vec<u8>),
+}
+
+struct State {
+    script: VecDeque<Entry>,
+    rx: VecDeque<u8>,
+    tx: Vec<u8>,
+    errors: Vec<String>,
+}
+
+impl State {
+    // Makes the device answers that follow the last matched command readable
+    fn release(&mut self) {
+        while let Some(Entry::Received(_)) = self.script.front() {
+            if let Some(Entry::Received(d)) = self.script.pop_front() {
+                self.rx.extend(d);
+            }
+        }
+    }
+
+    fn push_sent(&mut self, data: &[u8]) -> Result<()> {
+        self.tx.extend_from_slice(data);
+        while let Some(Entry::Sent(exp)) = self.script.front() {
+            let n=exp.len().min(self.tx.len());
+            if exp[..n] != self.tx[..n] {
+                let msg=format!("sent '{}' but the recording expects '{}'",escape(&self.tx),escape(exp));
+                self.tx.clear();
+                self.errors.push(msg.clone());
+                return Err(Error::new(ErrorKind::InvalidData,msg));
+            }
+            if n < exp.len() {
+                return Ok(());
+            }
+            self.tx.drain(..n);
+            self.script.pop_front();
+            self.release();
+        }
+        if !self.tx.is_empty() {
+            let msg=format!("sent '{}' after the end of the recording",escape(&self.tx));
+            self.tx.clear();
+            self.errors.push(msg.clone());
+            return Err(Error::new(ErrorKind::InvalidData,msg));
+        }
+        Ok(())
+    }
+}
+
+// Transport that plays back the device side of a transcript and checks
+// that the host side sends exactly the recorded bytes.
+#[derive(Clone)]
+pub struct Replay {
+    state: Arc<Mutex<State>>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text=std::fs::read_to_string(path)?;
+        Ok(Self::from_transcript(&text))
+    }
+
+    pub fn from_transcript(text: &str) -> Self {
+        let mut script=VecDeque::new();
+        for l in text.lines() {
+            match transcript::parse_line(l) {
+                Some((TX,data)) => script.push_back(Entry::Sent(data)),
+                Some((RX,data)) => script.push_back(Entry::Received(data)),
+                _ => {}
+            }
+        }
+        let mut state=State { script, rx: VecDeque::new(), tx: vec!(), errors: vec!() };
+        state.release();
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    // Commands still expected by the recording, without line terminator
+    pub fn commands(&self) -> Vec<String> {
+        let st=self.state.lock().unwrap();
+        st.script.iter().filter_map( |e| match e {
+            Entry::Sent(d) => Some(String::from_utf8_lossy(d).trim_end().to_string()),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn finish(&self) -> std::result::Result<(),Vec<String>> {
+        let st=self.state.lock().unwrap();
+        let mut errors=st.errors.clone();
+        for e in &st.script {
+            if let Entry::Sent(d) = e {
+                errors.push(format!("recorded command '{}' was never sent",escape(d)));
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl Read for Replay {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut st=self.state.lock().unwrap();
+        if st.rx.is_empty() {
+            return Err(Error::new(ErrorKind::TimedOut,"no more recorded data"));
+        }
+        let n=buf.len().min(st.rx.len());
+        for (dst,b) in buf.iter_mut().zip(st.rx.drain(..n)) {
+            *dst=b;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for Replay {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.state.lock().unwrap().push_sent(buf)?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for Replay {
+    fn set_timeout(&mut self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+    fn write_data_terminal_ready(&mut self, _level: bool) -> Result<()> {
+        Ok(())
+    }
+    fn write_request_to_send(&mut self, _level: bool) -> Result<()> {
+        Ok(())
+    }
+    fn set_baud_rate(&mut self, _baud: u32) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Outcome of a replay that matched the protocol code: short notes on what was
+// replayed and the spec drift of the last `show`, if any
+pub struct Replayed {
+    pub report: Vec<String>,
+    pub drift: Option<Drift>,
+}
+
+// Runs a recorded session through BorosSerial: reset, then every recorded
+// command through the same call the editor would use (item writes through
+// write_config, checked against the spec), with the `timing` given. Returns
+// what was replayed or the list of mismatches.
+pub fn check(path: &Path, root: &Root, timing: Timeouts) -> std::result::Result<Replayed,Vec<String>> {
+    let replay=Replay::load(path).map_err( |e| vec!(format!("{}: {}",path.display(),e)) )?;
+    let mut ser=BorosSerial::new(Box::new(replay.clone()));
+    ser.set_timeouts(timing);
+    let mut report=vec!();
+    let mut drift=None;
+    let mut errors=vec!();
+    let mut device: Option<&Device>=None;
+
+    if ser.connect() {
+        let cmds=replay.commands();
+        let mut i=0;
+        while i < cmds.len() {
+            // A run of item writes goes through write_config, as Write in the editor
+            let writes: Vec<(String,String)>=match device {
+                Some(dev) => cmds[i..].iter()
+                                .map_while( |c| c.split_once(' ').filter( |(id,_)| dev.find(id).is_some() ) )
+                                .map( |(id,raw)| (id.to_string(),raw.to_string()) )
+                                .collect(),
+                None => vec!(),
+            };
+            if let (Some(dev),false)=(device,writes.is_empty()) {
+                match ser.write_config(dev,&writes) {
+                    Ok(()) => report.push(format!("{} item(s) written",writes.len())),
+                    Err(e) => errors.push(format!("write: {}",e)),
+                }
+                i+=writes.len();
+                continue;
+            }
+            match cmds[i].as_str() {
+                "ver" => match ser.get_signature() {
+                    Ok(sig) => {
+                        device=root.device(&sig);
+                        if device.is_none() {
+                            errors.push(format!("signature {} has no spec",sig));
+                        }
+                        report.push(format!("signature {}",sig));
+                    },
+                    Err(e) => errors.push(format!("ver: {}",e)),
+                },
+                "show" => match ser.get_entries() {
+                    Ok(cfg) if !cfg.is_empty() => {
+                        report.push(format!("{} config entries",cfg.len()));
+                        // Drift is reported, not failed: the capture may predate the spec
+                        drift=device.map( |dev| Drift::check(dev,&cfg) ).filter( |d| !d.is_empty() );
+                    },
+                    Ok(_) => errors.push("show: no config entries parsed".into()),
+                    Err(e) => errors.push(format!("show: {}",e)),
+                },
+                c => match ser.do_cmd(c) {
+                    Ok((true,_)) => {},
+                    Ok((false,_)) => errors.push(format!("{}: device did not answer [OK]",c)),
+                    Err(e) => errors.push(format!("{}: {}",c,e)),
+                },
+            }
+            i+=1;
+        }
+    } else {
+        errors.push("no prompt after reset".into());
+    }
+
+    if let Err(e) = replay.finish() {
+        for m in e {
+            if !errors.iter().any( |x| x.ends_with(&m) ) {
+                errors.push(m);
+            }
+        }
+    }
+    if errors.is_empty() { Ok(Replayed { report, drift }) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-written sessions, not hardware captures: they check that the
+    // protocol code still reads and writes them against the built-in spec
+    #[test]
+    fn samples() {
+        let root=Root::builtin().unwrap();
+        let timing=Timeouts { command_gap_ms: 0, answer_ms: 0, ..Timeouts::default() };
+        let dir=Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let mut files: Vec<_>=std::fs::read_dir(&dir).unwrap().map( |e| e.unwrap().path() ).collect();
+        files.sort();
+        assert!(!files.is_empty());
+        for f in files {
+            if let Err(errors)=check(&f,&root,timing) {
+                panic!("{}: {}",f.display(),errors.join("; "));
+            }
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use serialport::{SerialPort};
+use std::time::{Duration,Instant};
+use std::thread::sleep;
+use std::io::{Read,Write,Result,Error,ErrorKind};
+use regex::Regex;
+
+use crate::spec::{Signature,Device,DeviceInfo,ConfigEntry};
+use crate::describe;
+use crate::transcript::Transcript;
+use crate::settings::{SerialSettings,ResetLine,Timeouts};
+use crate::ihex::Image;
+use crate::stk500;
+
+// Byte channel to the device. Implemented by real serial ports and by the
+// transcript replayer used for regression checks.
+pub trait Transport: Read + Write + Send {
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()>;
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()>;
+    fn write_request_to_send(&mut self, level: bool) -> Result<()>;
+    fn set_baud_rate(&mut self, baud: u32) -> Result<()>;
+}
+
+impl Transport for Box<dyn SerialPort> {
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        (**self).set_timeout(timeout).map_err(Error::from)
+    }
+    fn write_data_terminal_ready(&mut self, level: bool) -> Result<()> {
+        (**self).write_data_terminal_ready(level).map_err(Error::from)
+    }
+    fn write_request_to_send(&mut self, level: bool) -> Result<()> {
+        (**self).write_request_to_send(level).map_err(Error::from)
+    }
+    fn set_baud_rate(&mut self, baud: u32) -> Result<()> {
+        (**self).set_baud_rate(baud).map_err(Error::from)
+    }
+}
+
+// Pulses the reset line configured for the device
+pub fn pulse_reset(port: &mut dyn Transport, line: &SerialSettings) {
+    let pulse=Duration::from_millis(line.reset_pulse_ms);
+    match line.reset {
+        ResetLine::Dtr => {
+            let _=port.write_data_terminal_ready(true);
+            sleep(pulse);
+            let _=port.write_data_terminal_ready(false);
+        },
+        ResetLine::Rts => {
+            let _=port.write_request_to_send(true);
+            sleep(pulse);
+            let _=port.write_request_to_send(false);
+        },
+        ResetLine::None => {},
+    }
+}
+
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum ConnectMode {
+    Reset,   // Reboot the device and wait for the boot prompt
+    Attach,  // Keep the device running, just ask for a new prompt
+}
+
+// Commands sent since the port was opened, for the device information panel
+#[derive(Clone,Copy,Default,Debug)]
+pub struct CmdStats {
+    pub count: u32,
+    // Commands that failed or were not acknowledged with [OK]
+    pub errors: u32,
+    // Commands answered with a prompt, and the sum of the times from the end
+    // of their write to the prompt
+    pub answered: u32,
+    pub total: Duration,
+}
+
+impl CmdStats {
+    // Average latency of the answered commands
+    pub fn average(&self) -> Option<Duration> {
+        if self.answered == 0 { None } else { Some(self.total/self.answered) }
+    }
+}
+
+pub struct BorosSerial {
+    port : Box<dyn Transport>,
+    prompt: [u8;1],
+    found_prompt: bool,
+    transcript: Option<Transcript>,
+    line: SerialSettings,
+    timing: Timeouts,
+    stats: CmdStats,
+    // Why the link failed since the prompt was last found, until taken
+    link_error: Option<String>,
+}
+
+impl BorosSerial {
+
+    pub fn new(port: Box<dyn Transport>) -> Self {
+        BorosSerial::with_settings(port,SerialSettings::default())
+    }
+
+    pub fn with_settings(port: Box<dyn Transport>,line: SerialSettings) -> Self {
+        Self {
+            port: port,
+            prompt: [b'>'],
+            found_prompt: false,
+            transcript: None,
+            line: line,
+            timing: Timeouts::default(),
+            stats: CmdStats::default(),
+            link_error: None,
+        }
+    }
+
+    pub fn set_timeouts(&mut self,t: Timeouts) {
+        self.timing=t;
+    }
+
+    pub fn set_transcript(&mut self,t: Option<Transcript>) {
+        self.transcript=t;
+    }
+
+    pub fn stats(&self) -> CmdStats {
+        self.stats
+    }
+
+    fn log_note(&mut self,msg: &str) {
+        if let Some(t) = &mut self.transcript { t.note(msg) }
+    }
+
+    // Link failure found by the last commands (write error, or no answer to a
+    // command sent at the prompt); reported once
+    pub fn take_link_error(&mut self) -> Option<String> {
+        self.link_error.take()
+    }
+
+    fn send(&mut self,data: &[u8]) -> Result<()> {
+        if let Some(t) = &mut self.transcript { t.sent(data) }
+        let res=self.port.write_all(data);
+        if let Err(e) = &res {
+            self.link_error=Some(e.to_string());
+        }
+        res
+    }
+
+    pub fn open(&mut self,mode: ConnectMode) -> bool {
+        match mode {
+            ConnectMode::Reset => self.connect(),
+            ConnectMode::Attach => self.attach(),
+        }
+    }
+
+    pub fn attach(&mut self) -> bool {
+        let _=self.port.set_timeout(Duration::from_millis(self.timing.read_ms));
+        self.log_note("attach without reset");
+        if self.send(b"\n").is_err() {
+            return false;
+        }
+        self.wait_prompt()
+    }
+
+    // Gets the prompt back after a command the device did not answer
+    fn resync(&mut self) -> bool {
+        self.log_note("resync");
+        self.send(b"\n").is_ok() && self.wait_prompt()
+    }
+
+    pub fn connect(&mut self) -> bool {
+        let _=self.port.set_timeout(Duration::from_millis(self.timing.read_ms));
+        // Reset via the configured modem line
+        sleep(Duration::from_millis(500));
+        self.log_note(match self.line.reset {
+            ResetLine::Dtr => "DTR pulse",
+            ResetLine::Rts => "RTS pulse",
+            ResetLine::None => "no reset line",
+        });
+        pulse_reset(self.port.as_mut(),&self.line);
+        sleep(Duration::from_millis(self.line.boot_delay_ms));
+        // wait for prompt
+        self.wait_prompt()
+    }
+
+    // Uploads a firmware image through the bootloader. The device must be
+    // reconnected afterwards.
+    pub fn flash(&mut self,image: &Image,progress: &mut dyn FnMut(&str,usize,usize)) -> Result<[u8;3]> {
+        self.found_prompt=false;
+        self.log_note(&format!("flashing {} bytes at {} baud",image.data.len(),self.line.bootloader_baud));
+        let res=stk500::program(self.port.as_mut(),&self.line,image,progress);
+        match &res {
+            Ok(_) => self.log_note("flash written and verified"),
+            Err(e) => self.log_note(&format!("flash failed: {}",e)),
+        }
+        res
+    }
+
+    fn wait_prompt(&mut self) -> bool {
+        let mut c: [u8;1] = [0;1];
+        let mut rx=vec!();
+        self.found_prompt = false;
+        while self.port.read_exact(&mut c).is_ok()  {
+            rx.push(c[0]);
+            if c[0] == b'\n' && self.port.read_exact(&mut c).is_ok() {
+                rx.push(c[0]);
+                if c == self.prompt {
+                    self.found_prompt=true;
+                    break;
+                }
+            }
+        }
+        if let Some(t) = &mut self.transcript { t.received(&rx) }
+        if self.found_prompt {
+            self.link_error=None;
+        }
+        self.found_prompt
+    }
+
+    pub fn do_cmd(&mut self,cmd:&str) -> Result<(bool,Vec<String>)> {
+        let res=self.exchange(cmd);
+        self.stats.count+=1;
+        if !matches!(res,Ok((true,_))) {
+            self.stats.errors+=1;
+        }
+        res
+    }
+
+    fn exchange(&mut self,cmd:&str) -> Result<(bool,Vec<String>)> {
+        if !self.found_prompt {
+            Err(Error::from(ErrorKind::NotConnected))
+        } else {
+            let prompt=String::from_utf8_lossy(&self.prompt).into_owned();
+            let prompt=prompt.as_str();
+            sleep(Duration::from_millis(self.timing.command_gap_ms));
+            self.found_prompt=false;
+            let mut out=cmd.as_bytes().to_vec();
+            out.push(b'\n');
+            self.send(&out)?;
+            // Read while waiting for the answer, so the prompt is timed when it arrives
+            let sent=Instant::now();
+            let answer=Duration::from_millis(self.timing.answer_ms);
+            let mut prompt_at=None;
+            let mut buf = String::new();
+            let mut rx = vec!();
+            let mut c: [u8;1] = [0;1];
+            loop {
+                match self.port.read_exact(&mut c) {
+                    Ok(()) => {
+                        if c == self.prompt { prompt_at=Some(sent.elapsed()) }
+                        buf.push(c[0] as char);
+                        rx.push(c[0]);
+                    },
+                    Err(_) if sent.elapsed() < answer => sleep(Duration::from_millis(1)),
+                    Err(_) => break,
+                }
+            }
+            if let Some(t) = &mut self.transcript { t.received(&rx) }
+            // Not even the echo of a command sent at the prompt
+            if rx.is_empty() {
+                let e=Error::new(ErrorKind::TimedOut,format!("no answer to '{}'",cmd));
+                self.link_error=Some(e.to_string());
+                return Err(e);
+            }
+            let mut lines : Vec<String>=buf.split('\n').filter_map(|x| {
+               let tr=x.trim();
+               if tr.is_empty() || tr== cmd {
+                    None
+               } else {
+                   Some(tr.to_string())
+               }
+            }).collect();
+            // Detect promt & remove it from ouput
+            self.found_prompt=lines.iter().find( |x| *x==prompt).is_some();
+            lines.retain( |x| *x!=prompt);
+            if let (true,Some(t))=(self.found_prompt,prompt_at) {
+                self.stats.answered+=1;
+                self.stats.total+=t;
+            }
+            let res=lines.iter().find( |x| *x=="[OK]" );
+
+            Ok((res.is_some(),lines))
+        }
+    }
+
+    pub fn get_signature(&mut self) -> Result<Signature> {
+        Ok(self.get_info()?.signature)
+    }
+
+    // Parses the `ver` banner: <name> [<product><<model>>V<version>] <build>
+    pub fn get_info(&mut self) -> Result<DeviceInfo> {
+        let (res,lines) = self.do_cmd("ver")?;
+        if res && lines.len()>=1 {
+            let rx= Regex::new(r"(.+)\[(.+)<(.+)>V(\d+)\](.+)").unwrap();
+            if let Some(cap)= rx.captures(lines[0].as_str()) {
+                return Ok(DeviceInfo {
+                    banner: lines[0].clone(),
+                    name: cap[1].trim().into(),
+                    signature: Signature::new(&cap[2],&cap[3],&cap[4]),
+                    build: cap[5].trim().into(),
+                });
+            }
+        } 
+        Err(Error::new(ErrorKind::Other, "Can't read device signature"))
+    
+    }
+
+    pub fn get_config(&mut self) -> Result<Vec<(String,String)>> {
+        Ok(ConfigEntry::values(&self.get_entries()?))
+    }
+
+    // Every `[id] label:value` line of `show`
+    pub fn get_entries(&mut self) -> Result<Vec<ConfigEntry>> {
+        let (res,lines) = self.do_cmd("show")?;
+        if !res {
+            return Err(Error::new(ErrorKind::InvalidData,"The device did not acknowledge 'show'"));
+        }
+        let mut ret=vec!();
+        let re = Regex::new(r"^\[(.+)\](.*):(.+)").unwrap();
+        for l in &lines {
+            if let Some(cap) = re.captures(l) {
+                ret.push(ConfigEntry { id: cap[1].into(), label: cap[2].trim().into(), raw_value: cap[3].into() });
+            }
+        }
+        Ok(ret)
+    }
+
+    // Asks the firmware to describe its parameters (`spec`, else `help`)
+    pub fn get_spec(&mut self,sig: &Signature) -> Result<Device> {
+        let mut last=String::from("no answer");
+        for cmd in &["spec","help"] {
+            match self.do_cmd(cmd) {
+                Ok((true,lines)) => match describe::parse(sig,&lines) {
+                    Ok(dev) => return Ok(dev),
+                    Err(e) => last=format!("'{}': {}",cmd,e),
+                },
+                Ok((false,_)) => last=format!("'{}' is not supported",cmd),
+                // Older firmwares may not answer an unknown command at all
+                Err(e) => {
+                    last=format!("'{}': {}",cmd,e);
+                    if !self.resync() {
+                        return Err(e);
+                    }
+                },
+            }
+        }
+        Err(Error::new(ErrorKind::InvalidData,format!("The device can't describe itself ({})",last)))
+    }
+
+    // Device model for a signature missing from the spec: the firmware
+    // description if it has one, else text fields built from `show`
+    pub fn describe_device(&mut self,sig: &Signature) -> Result<Device> {
+        let entries=self.get_entries();
+        match (self.get_spec(sig),entries) {
+            (Ok(mut dev),entries) => {
+                dev.add_unknown(&entries.unwrap_or_default());
+                Ok(dev)
+            },
+            (Err(_),Ok(entries)) if !entries.is_empty() => Ok(Device::generic(sig,&entries)),
+            (Err(e),_) => Err(e),
+        }
+    }
+
+    // Validates `values` against the spec of the device and writes them one by
+    // one, stopping at the first the device does not acknowledge. A value the
+    // device sent and the spec rejects (ConfItem::kept) is written back as is.
+    pub fn write_config(&mut self,dev: &Device,values: &[(String,String)]) -> Result<()> {
+        for (id,raw) in values {
+            let item=dev.find(id).ok_or_else( || Error::new(ErrorKind::InvalidInput,format!("'{}' is not an item of {}",id,dev.signature)) )?;
+            if item.kept() != Some(raw.as_str()) {
+                item.parse(raw).map_err( |e| Error::new(ErrorKind::InvalidInput,e) )?;
+            }
+            let (ok,_)=self.do_cmd(&item.to_command(raw))?;
+            if !ok {
+                return Err(Error::new(ErrorKind::Other,format!("the device rejected '{}'",item.caption())));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::Replay;
+
+    fn device(recording: &str) -> BorosSerial {
+        let mut ser=BorosSerial::new(Box::new(Replay::from_transcript(recording)));
+        ser.set_timeouts(Timeouts { command_gap_ms: 0, answer_ms: 0, ..Timeouts::default() });
+        assert!(ser.connect());
+        ser
+    }
+
+    #[test]
+    fn show_not_acknowledged_is_an_error() {
+        let mut ser=device("t << \\r\\n>\nt >> show\\n\nt << show\\r\\nunknown command\\r\\n>\n");
+        assert!(ser.get_entries().is_err());
+        assert!(ser.take_link_error().is_none());
+    }
+
+    #[test]
+    fn silent_device_is_a_link_error() {
+        let mut ser=device("t << \\r\\n>\nt >> ver\\n\n");
+        assert_eq!(ser.do_cmd("ver").unwrap_err().kind(),ErrorKind::TimedOut);
+        assert!(ser.take_link_error().is_some());
+        assert!(ser.take_link_error().is_none());
+        assert_eq!(ser.do_cmd("ver").unwrap_err().kind(),ErrorKind::NotConnected);
+    }
+
+    #[test]
+    fn get_spec_falls_back_to_help() {
+        let mut ser=device("t << \\r\\n>\nt >> spec\\n\nt >> \\n\nt << \\r\\n>\nt >> help\\n\nt << help\\r\\n[cha] int|Channel|76|0|125\\r\\n[OK]\\r\\n>\n");
+        let dev=ser.get_spec(&Signature::new("BM","24M","9")).unwrap();
+        assert_eq!(dev.find("cha").unwrap().raw(),"76");
+        assert!(ser.take_link_error().is_none());
+    }
+
+    #[test]
+    fn write_config_needs_ok() {
+        let sig=Signature::new("BM","24M","4");
+        let mut dev=describe::parse(&sig,&["[cha] int|Channel|76|0|125".to_string(),"[txp] int|Power|3|0|3".to_string()]).unwrap();
+        assert!(dev.find_mut("txp").unwrap().from_device("9").is_err());
+        let mut ser=device("t << \\r\\n>\nt >> txp 9\\n\nt << txp 9\\r\\n[OK]\\r\\n>\nt >> cha 90\\n\nt << cha 90\\r\\nout of range\\r\\n>\n");
+        // txp keeps the 9 the device sent, cha is refused by the device
+        assert!(ser.write_config(&dev,&[("txp".into(),"9".into())]).is_ok());
+        assert!(ser.write_config(&dev,&[("cha".into(),"90".into())]).is_err());
+        assert!(ser.write_config(&dev,&[("cha".into(),"200".into())]).is_err());
+    }
+
+    #[test]
+    fn latency_excludes_the_configured_delays() {
+        let mut ser=device("t << \\r\\n>\nt >> ver\\n\nt << ver\\r\\nBM 24M 4\\r\\n[OK]\\r\\n>\nt >> fac\\n\nt << fac\\r\\nunknown\\r\\n>\n");
+        ser.set_timeouts(Timeouts { command_gap_ms: 100, answer_ms: 200, ..Timeouts::default() });
+        assert!(ser.do_cmd("ver").unwrap().0);
+        assert!(!ser.do_cmd("fac").unwrap().0);
+        let st=ser.stats();
+        assert_eq!((st.count,st.errors,st.answered),(2,1,2));
+        assert!(st.average().unwrap() < Duration::from_millis(100));
+    }
+}
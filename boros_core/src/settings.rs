@@ -0,0 +1,210 @@
+use serde::{Deserialize,Serialize};
+use serialport::{SerialPort,SerialPortSettings,DataBits,StopBits};
+use std::time::Duration;
+
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+pub enum Parity { None, Odd, Even }
+
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+pub enum FlowControl { None, Software, Hardware }
+
+// Modem line wired to the MCU reset pin
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+pub enum ResetLine { Dtr, Rts, None }
+
+#[derive(Deserialize,Clone,Copy,PartialEq,Debug)]
+#[serde(default)]
+pub struct SerialSettings {
+    pub baud: u32,
+    pub data_bits: u8,
+    pub parity: Parity,
+    pub stop_bits: u8,
+    pub flow_control: FlowControl,
+    pub reset: ResetLine,
+    pub reset_pulse_ms: u64,
+    pub boot_delay_ms: u64,
+    pub bootloader_baud: u32,
+    // Program flash of the MCU in bytes, bounds firmware images
+    pub flash_size: usize,
+}
+
+impl Default for SerialSettings {
+    fn default() -> Self {
+        Self {
+            baud: 9600,
+            data_bits: 8,
+            parity: Parity::None,
+            stop_bits: 1,
+            flow_control: FlowControl::None,
+            reset: ResetLine::Dtr,
+            reset_pulse_ms: 100,
+            boot_delay_ms: 1500,
+            bootloader_baud: 57600,
+            flash_size: 32*1024,
+        }
+    }
+}
+
+impl SerialSettings {
+    pub fn port_settings(&self) -> SerialPortSettings {
+        SerialPortSettings {
+            baud_rate: self.baud,
+            data_bits: match self.data_bits {
+                5 => DataBits::Five,
+                6 => DataBits::Six,
+                7 => DataBits::Seven,
+                _ => DataBits::Eight,
+            },
+            parity: match self.parity {
+                Parity::None => serialport::Parity::None,
+                Parity::Odd => serialport::Parity::Odd,
+                Parity::Even => serialport::Parity::Even,
+            },
+            stop_bits: if self.stop_bits == 2 { StopBits::Two } else { StopBits::One },
+            flow_control: match self.flow_control {
+                FlowControl::None => serialport::FlowControl::None,
+                FlowControl::Software => serialport::FlowControl::Software,
+                FlowControl::Hardware => serialport::FlowControl::Hardware,
+            },
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    pub fn open(&self, port: &str) -> serialport::Result<Box<dyn SerialPort>> {
+        serialport::open_with_settings(port,&self.port_settings())
+    }
+
+    pub fn with(&self, ov: &SerialOverride) -> Self {
+        Self {
+            baud: ov.baud.unwrap_or(self.baud),
+            data_bits: ov.data_bits.unwrap_or(self.data_bits),
+            parity: ov.parity.unwrap_or(self.parity),
+            stop_bits: ov.stop_bits.unwrap_or(self.stop_bits),
+            flow_control: ov.flow_control.unwrap_or(self.flow_control),
+            reset: ov.reset.unwrap_or(self.reset),
+            reset_pulse_ms: ov.reset_pulse_ms.unwrap_or(self.reset_pulse_ms),
+            boot_delay_ms: ov.boot_delay_ms.unwrap_or(self.boot_delay_ms),
+            bootloader_baud: ov.bootloader_baud.unwrap_or(self.bootloader_baud),
+            flash_size: self.flash_size,
+        }
+    }
+}
+
+// User supplied values that take precedence over the spec
+#[derive(Serialize,Deserialize,Default,Clone,Copy,PartialEq,Debug)]
+#[serde(default)]
+pub struct SerialOverride {
+    pub baud: Option<u32>,
+    pub data_bits: Option<u8>,
+    pub parity: Option<Parity>,
+    pub stop_bits: Option<u8>,
+    pub flow_control: Option<FlowControl>,
+    pub reset: Option<ResetLine>,
+    pub reset_pulse_ms: Option<u64>,
+    pub boot_delay_ms: Option<u64>,
+    pub bootloader_baud: Option<u32>,
+}
+
+// Option names shared by the command line and the preferences dialog
+pub const OVERRIDE_ARGS: [(&str,&str);9] = [
+    ("baud","Baud rate"),
+    ("data-bits","Data bits"),
+    ("parity","Parity"),
+    ("stop-bits","Stop bits"),
+    ("flow","Flow control"),
+    ("reset","Reset line"),
+    ("reset-pulse","Reset pulse (ms)"),
+    ("boot-delay","Boot delay (ms)"),
+    ("bootloader-baud","Bootloader baud rate"),
+];
+
+impl SerialOverride {
+    // Values of `other` win over ours
+    pub fn or(&self, other: &SerialOverride) -> Self {
+        Self {
+            baud: other.baud.or(self.baud),
+            data_bits: other.data_bits.or(self.data_bits),
+            parity: other.parity.or(self.parity),
+            stop_bits: other.stop_bits.or(self.stop_bits),
+            flow_control: other.flow_control.or(self.flow_control),
+            reset: other.reset.or(self.reset),
+            reset_pulse_ms: other.reset_pulse_ms.or(self.reset_pulse_ms),
+            boot_delay_ms: other.boot_delay_ms.or(self.boot_delay_ms),
+            bootloader_baud: other.bootloader_baud.or(self.bootloader_baud),
+        }
+    }
+
+    // Current value of an option as `parse_arg` accepts it
+    pub fn arg_value(&self, name: &str) -> Option<String> {
+        match name {
+            "baud" => self.baud.map( |v| v.to_string() ),
+            "data-bits" => self.data_bits.map( |v| v.to_string() ),
+            "parity" => self.parity.map( |v| format!("{:?}",v).to_lowercase() ),
+            "stop-bits" => self.stop_bits.map( |v| v.to_string() ),
+            "flow" => self.flow_control.map( |v| format!("{:?}",v).to_lowercase() ),
+            "reset" => self.reset.map( |v| format!("{:?}",v).to_lowercase() ),
+            "reset-pulse" => self.reset_pulse_ms.map( |v| v.to_string() ),
+            "boot-delay" => self.boot_delay_ms.map( |v| v.to_string() ),
+            "bootloader-baud" => self.bootloader_baud.map( |v| v.to_string() ),
+            _ => None,
+        }
+    }
+
+    // Parses one `--<name> <value>` command line pair
+    pub fn parse_arg(&mut self, name: &str, v: &str) -> Result<(),String> {
+        let bad=|| format!("Invalid value '{}' for --{}",v,name);
+        match name {
+            "baud" => self.baud=Some(v.parse().map_err(|_| bad())?),
+            "data-bits" => match v.parse::<u8>() {
+                Ok(n) if (5..=8).contains(&n) => self.data_bits=Some(n),
+                _ => return Err(bad()),
+            },
+            "parity" => self.parity=Some(match v.to_lowercase().as_str() {
+                "none" | "n" => Parity::None,
+                "odd" | "o" => Parity::Odd,
+                "even" | "e" => Parity::Even,
+                _ => return Err(bad()),
+            }),
+            "stop-bits" => match v {
+                "1" => self.stop_bits=Some(1),
+                "2" => self.stop_bits=Some(2),
+                _ => return Err(bad()),
+            },
+            "flow" => self.flow_control=Some(match v.to_lowercase().as_str() {
+                "none" => FlowControl::None,
+                "software" | "xonxoff" => FlowControl::Software,
+                "hardware" | "rtscts" => FlowControl::Hardware,
+                _ => return Err(bad()),
+            }),
+            "reset" => self.reset=Some(match v.to_lowercase().as_str() {
+                "dtr" => ResetLine::Dtr,
+                "rts" => ResetLine::Rts,
+                "none" => ResetLine::None,
+                _ => return Err(bad()),
+            }),
+            "reset-pulse" => self.reset_pulse_ms=Some(v.parse().map_err(|_| bad())?),
+            "boot-delay" => self.boot_delay_ms=Some(v.parse().map_err(|_| bad())?),
+            "bootloader-baud" => self.bootloader_baud=Some(v.parse().map_err(|_| bad())?),
+            _ => return Err(format!("Unknown serial option --{}",name)),
+        }
+        Ok(())
+    }
+}
+
+// Protocol timing, all in milliseconds
+#[derive(Serialize,Deserialize,Clone,Copy,PartialEq,Debug)]
+#[serde(default)]
+pub struct Timeouts {
+    // Read timeout of the port, ends an answer
+    pub read_ms: u64,
+    // Pause before sending a command
+    pub command_gap_ms: u64,
+    // Time given to the device to answer before reading
+    pub answer_ms: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self { read_ms: 2000, command_gap_ms: 100, answer_ms: 500 }
+    }
+}
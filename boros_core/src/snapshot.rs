@@ -0,0 +1,33 @@
+use serde::{Deserialize,Serialize};
+use std::collections::BTreeMap;
+use std::io::{Result,Error,ErrorKind};
+use std::path::Path;
+
+use crate::spec::Signature;
+
+// Device configuration as stored on disk: raw values keyed by item id
+#[derive(Serialize,Deserialize)]
+pub struct ConfigFile {
+    pub signature: Signature,
+    pub values: BTreeMap<String,String>,
+}
+
+impl ConfigFile {
+    pub fn new(signature: Signature, values: Vec<(String,String)>) -> Self {
+        Self { signature, values: values.into_iter().collect() }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text=std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&text).map_err( |e| Error::new(ErrorKind::InvalidData,e.to_string()) )
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text=serde_yaml::to_string(self).map_err( |e| Error::new(ErrorKind::InvalidData,e.to_string()) )?;
+        std::fs::write(path,text)
+    }
+
+    pub fn values(&self) -> Vec<(String,String)> {
+        self.values.iter().map( |(k,v)| (k.clone(),v.clone()) ).collect()
+    }
+}
@@ -0,0 +1,767 @@
+use serde::{Deserialize,Serialize};
+use serde_yaml::{Result,from_str};
+use regex::Regex;
+use std::path::PathBuf;
+use std::collections::BTreeMap;
+
+use crate::settings::{SerialSettings,SerialOverride};
+use crate::migrate::{Migration,Rule};
+use crate::lang;
+use crate::devices::yml;
+
+#[derive(Deserialize,Clone)]
+pub struct Root {
+    pub spec: Vec<Device>,
+    #[serde(default)]
+    pub migrations: Vec<Migration>,
+}
+
+impl Root {
+    // First readable spec of `paths`, else the built-in one. The files skipped
+    // on the way are returned as "<path>: <error>" for the caller to report.
+    pub fn load(paths: &[PathBuf]) -> Result<(Self,Vec<String>)> {
+        let mut skipped=vec!();
+        for p in paths {
+            match std::fs::read_to_string(p).map(|s| from_str::<Root>(&s)) {
+                Ok(Ok(r)) => return Ok((r,skipped)),
+                Ok(Err(e)) => skipped.push(format!("{}: {}",p.display(),e)),
+                Err(e) => skipped.push(format!("{}: {}",p.display(),e)),
+            }
+        }
+        Ok((Self::builtin()?,skipped))
+    }
+
+    // The spec embedded by embeed_spec.sh
+    pub fn builtin() -> Result<Self> {
+        from_str(yml)
+    }
+
+    pub fn find(&self,sig: &Signature) -> Option<usize> {
+        self.spec.iter().position( |e| e.signature == *sig )
+    }
+
+    // Adds a device not in the spec files (e.g. described by its firmware)
+    pub fn add(&mut self,dev: Device) -> usize {
+        match self.find(&dev.signature) {
+            Some(n) => { self.spec[n]=dev; n },
+            None => { self.spec.push(dev); self.spec.len()-1 },
+        }
+    }
+
+    pub fn device(&self,sig: &Signature) -> Option<&Device> {
+        self.find(sig).map( |n| &self.spec[n] )
+    }
+
+    // Specs of later firmware versions for the same product and model, oldest first
+    pub fn newer(&self,sig: &Signature) -> Vec<&Device> {
+        let mut v: Vec<&Device>=self.spec.iter().filter( |d| d.signature.same_model(sig) && d.signature.version > sig.version ).collect();
+        v.sort_by_key( |d| d.signature.version );
+        v
+    }
+
+    // Migration rules to carry a config from version `from` up to `to`, chaining
+    // the longest available steps. Versions without a migration entry are compatible.
+    pub fn upgrade_rules(&self,from: &Signature,to: &Signature) -> Vec<Rule> {
+        let mut rules=vec!();
+        let mut cur=from.version;
+        while cur < to.version {
+            let step=self.migrations.iter()
+                        .filter( |m| m.product==from.product && m.model==from.model && m.from==cur && m.to > cur && m.to <= to.version )
+                        .max_by_key( |m| m.to );
+            // Spec files of the first format list the rules in the release of
+            // the next version instead
+            let release=self.spec.iter()
+                        .filter( |d| d.signature.product==from.product && d.signature.model==from.model && d.signature.version > cur && d.signature.version <= to.version )
+                        .min_by_key( |d| d.signature.version )
+                        .filter( |d| !d.release.migrate.is_empty() );
+            match (step,release) {
+                (Some(m),_) => {
+                    rules.extend(m.rules.iter().cloned());
+                    cur=m.to;
+                },
+                (None,Some(d)) => {
+                    rules.extend(d.release.migrate.iter().cloned());
+                    cur=d.signature.version;
+                },
+                (None,None) => cur+=1,
+            }
+        }
+        rules
+    }
+
+    // Line settings to try when opening a port whose device is not known yet
+    pub fn candidate_settings(&self,ov: &SerialOverride) -> Vec<SerialSettings> {
+        let mut c: Vec<SerialSettings>=vec!();
+        for s in self.spec.iter().map( |d| d.serial.unwrap_or_default().with(ov) ) {
+            if !c.contains(&s) { c.push(s) }
+        }
+        if c.is_empty() { c.push(SerialSettings::default().with(ov)) }
+        c
+    }
+}
+
+#[derive(Deserialize,Clone)]
+pub struct Device {
+    pub signature: Signature,
+    pub title: Localized,
+    #[serde(default)]
+    pub serial: Option<SerialSettings>,
+    #[serde(default)]
+    pub release: Release,
+    pub sections: Vec<Section>,
+    // Built from what the firmware reported, not read from the spec
+    #[serde(skip)]
+    pub described: bool,
+}
+
+// Release notes of a firmware version
+#[derive(Deserialize,Default,Clone)]
+pub struct Release {
+    #[serde(default)]
+    pub changelog: Localized,
+    #[serde(default)]
+    pub min_gui: Option<String>,
+    // Rules since the previous version of the same product/model. Superseded
+    // by the top level `migrations:`, still read from older spec files.
+    #[serde(default)]
+    pub migrate: Vec<Rule>,
+}
+
+fn version_tuple(v: &str) -> Vec<u32> {
+    v.split('.').map( |x| x.trim().parse::<u32>().unwrap_or(0) ).collect()
+}
+
+// True if dotted `version` is `min` or later
+pub fn version_at_least(version: &str, min: &str) -> bool {
+    version_tuple(version) >= version_tuple(min)
+}
+
+impl Device {
+    pub fn find(&self, id: &str) -> Option<&ConfItem> {
+        self.items().find( |i| i.is(id) )
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut ConfItem> {
+        self.items_mut().find( |i| i.is(id) )
+    }
+
+    // All items, section by section
+    pub fn items(&self) -> impl Iterator<Item=&ConfItem> {
+        self.sections.iter().flat_map( |s| s.items.iter() )
+    }
+
+    pub fn items_mut(&mut self) -> impl Iterator<Item=&mut ConfItem> {
+        self.sections.iter_mut().flat_map( |s| s.items.iter_mut() )
+    }
+
+    // Current (id,raw value) pairs of all items
+    pub fn values(&self) -> Vec<(String,String)> {
+        self.items().map( |i| (i.id().to_string(),i.raw()) ).collect()
+    }
+
+    // Sets raw values; returns the ids that are unknown or whose value is invalid
+    pub fn apply(&mut self, values: &[(String,String)]) -> Vec<String> {
+        let mut rejected=vec!();
+        for (id,raw) in values {
+            match self.find_mut(id) {
+                Some(item) => if let Err(e)=item.from_raw(raw) { rejected.push(format!("{}: {}",id,e)) },
+                None => rejected.push(format!("{}: not in spec",id)),
+            }
+        }
+        rejected
+    }
+
+    // Adds a Text item for every entry read with `show` the device does not
+    // describe, in an Other section (Parameters if it is the only one). The
+    // items have no default: the value read is all that is known. Returns how
+    // many were added.
+    pub fn add_unknown(&mut self, entries: &[ConfigEntry]) -> usize {
+        let mut items=vec!();
+        for e in entries {
+            if self.find(&e.id).is_none() {
+                let caption=if e.label.is_empty() { e.id.clone() } else { e.label.clone() };
+                let mut item=ConfItem::from(ItemKind::Text { id: e.id.clone(), caption: caption.into(), val: e.raw_value.clone(), maxlen: e.raw_value.len().max(64) });
+                let _=item.from_raw(&e.raw_value);
+                item.no_default=true;
+                items.push(item);
+            }
+        }
+        let n=items.len();
+        if n > 0 {
+            let generated=if self.sections.is_empty() { Generated::Parameters } else { Generated::Other };
+            self.sections.push(Section { name: Localized::default(), help: Localized::default(), items, generated: Some(generated) });
+        }
+        n
+    }
+
+    // Generic model of a device known only by its `show` output
+    pub fn generic(sig: &Signature, entries: &[ConfigEntry]) -> Device {
+        let mut dev=Device {
+            signature: sig.clone(),
+            title: sig.to_string().into(),
+            serial: None,
+            release: Release::default(),
+            sections: vec!(),
+            described: true,
+        };
+        dev.add_unknown(entries);
+        dev
+    }
+
+    pub fn reset(&mut self) {
+        for i in self.items_mut() {
+            i.reset();
+        }
+    }
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+pub struct Signature {
+    pub product: String,
+    pub model: String,
+    pub version: i32,
+}
+impl Signature {
+    pub fn new(p:&str,m:&str,v:&str) -> Self {
+        let v = v.parse::<i32>().unwrap_or(1i32);
+        Self {
+            product: String::from(p),
+            model: String::from(m),
+            version: v,
+        }
+    }
+    pub fn same_model(&self,other: &Signature) -> bool {
+        self.product == other.product && self.model == other.model
+    }
+}
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,"{}<{}>V{}",self.product,self.model,self.version)
+    }
+}
+impl PartialEq for Signature {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_model(other) && self.version==other.version
+    }
+}
+
+// What the firmware says about itself in the `ver` banner, e.g.
+// "Boros Met 24 [BM<24M>V4] build Nov  2 2020"
+#[derive(Serialize,Deserialize,Debug,Clone)]
+pub struct DeviceInfo {
+    pub banner: String,
+    pub name: String,
+    pub signature: Signature,
+    pub build: String,
+}
+
+// One `[id] label:value` line of `show`; the label is the firmware's own caption
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+pub struct ConfigEntry {
+    pub id: String,
+    pub label: String,
+    pub raw_value: String,
+}
+
+impl ConfigEntry {
+    pub fn values(entries: &[ConfigEntry]) -> Vec<(String,String)> {
+        entries.iter().map( |e| (e.id.clone(),e.raw_value.clone()) ).collect()
+    }
+}
+
+
+// Text of the spec shown to the user: a plain string or one per language,
+// e.g. `caption: { en: Channel, es: Canal, de: Kanal }`
+#[derive(Deserialize,Clone,Debug)]
+#[serde(untagged)]
+pub enum Localized {
+    Plain(String),
+    Translated(BTreeMap<String,String>),
+}
+
+impl Localized {
+    // In the selected language, else English
+    pub fn get(&self) -> &str {
+        match self {
+            Localized::Plain(s) => s,
+            Localized::Translated(t) => t.get(lang::current()).map(String::as_str).unwrap_or_else( || self.en() ),
+        }
+    }
+
+    // English text, the one firmwares print
+    pub fn en(&self) -> &str {
+        match self {
+            Localized::Plain(s) => s,
+            Localized::Translated(t) => t.get("en").or_else( || t.values().next() ).map(String::as_str).unwrap_or(""),
+        }
+    }
+}
+
+impl Default for Localized {
+    fn default() -> Self {
+        Localized::Plain(String::new())
+    }
+}
+
+impl From<String> for Localized {
+    fn from(s: String) -> Self {
+        Localized::Plain(s)
+    }
+}
+
+impl From<&str> for Localized {
+    fn from(s: &str) -> Self {
+        Localized::Plain(s.into())
+    }
+}
+
+impl std::fmt::Display for Localized {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.get())
+    }
+}
+
+#[derive(Deserialize,Clone)]
+pub struct Section {
+    pub name: Localized,
+    pub help: Localized,
+    pub items: Vec<ConfItem>,
+    // Set on sections made up by boros_core, which has no name for them
+    #[serde(skip)]
+    pub generated: Option<Generated>,
+}
+
+// Sections boros_core adds when a spec or a firmware description lacks them.
+// Their name and help are up to the GUI, in its language.
+#[derive(Clone,Copy,PartialEq,Debug)]
+pub enum Generated {
+    // Items a firmware describes before its first section
+    General,
+    // Entries of `show` missing from the spec or description of the device
+    Other,
+    // Every entry of `show` of a device with no spec nor description
+    Parameters,
+}
+
+#[derive(Deserialize,Clone,Debug)]
+pub struct Choice {
+    pub val: i32,
+    pub desc: Localized,
+}
+
+// Kind and limits of a configuration item, as written in spec.yml
+#[derive(Deserialize,Clone,Debug)]
+pub enum ItemKind {
+    Int    { id: String, caption:Localized, val:i32,  vmax:i32, vmin:i32 },
+    Hex    { id: String, caption:Localized,val:String, maxlen:usize, lsb:bool },
+    Text   { id: String, caption:Localized, val:String, maxlen:usize },
+    Choice { id: String, caption:Localized ,val:usize,  values:Vec<Choice> },
+    Check  { id: String, caption:Localized, val:bool },
+}
+
+// Typed value of an item. Hex digits are kept in the order the user reads
+// them, Choice holds the device value (not the position in the list).
+#[derive(Clone,Debug,PartialEq)]
+pub enum Value {
+    Int(i32),
+    Hex(String),
+    Text(String),
+    Choice(i32),
+    Check(bool),
+}
+
+// Configuration item of the spec with its current value. Devices exchange
+// values as the raw strings they print in `show` and accept in `<id> <value>`.
+#[derive(Deserialize,Clone,Debug)]
+#[serde(from="ItemKind")]
+pub struct ConfItem {
+    pub kind: ItemKind,
+    value: Value,
+    // Device value the spec does not accept, kept as is until the item is set
+    #[serde(skip)]
+    kept: Option<String>,
+    // Items built from `show` alone have no spec default
+    #[serde(skip)]
+    no_default: bool,
+}
+
+impl From<ItemKind> for ConfItem {
+    fn from(kind: ItemKind) -> Self {
+        let mut item=ConfItem { kind, value: Value::Int(0), kept: None, no_default: false };
+        item.value=item.default_value();
+        item
+    }
+}
+
+impl ConfItem {
+    pub fn is(&self,wid:&str) -> bool {
+        self.id()==wid
+    }
+
+    pub fn id(&self) -> &str {
+        match &self.kind {
+            ItemKind::Check { id , .. } |
+            ItemKind::Int { id , .. } |
+            ItemKind::Hex { id, .. } |
+            ItemKind::Choice { id, .. } |
+            ItemKind::Text { id , .. } => id
+        }
+    }
+
+    fn caption_text(&self) -> &Localized {
+        match &self.kind {
+            ItemKind::Check { caption , .. } |
+            ItemKind::Int { caption , .. } |
+            ItemKind::Hex { caption, .. } |
+            ItemKind::Choice { caption, .. } |
+            ItemKind::Text { caption , .. } => caption
+        }
+    }
+
+    pub fn caption(&self) -> &str {
+        self.caption_text().get()
+    }
+
+    // English caption, as printed by the firmware in `show`
+    pub fn caption_en(&self) -> &str {
+        self.caption_text().en()
+    }
+
+    pub fn is_hex(s:&str) -> bool {
+        let re = Regex::new(r"[0123456789abcdefABCDEF]").unwrap();
+        !s.is_empty() && s.len() % 2 == 0 && re.find_iter(s).count() == s.len()
+    }
+
+    // Reverses the byte order of a hex string
+    pub fn invert(s:&str) -> String {
+        let re = Regex::new(r"[0123456789abcdefABCDEF][0123456789abcdefABCDEF]").unwrap();
+        let mut x :Vec<&str>=re.find_iter(s).map(|x| { x.as_str() }).collect();
+        x.reverse();
+        x.join("")
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn default_value(&self) -> Value {
+        match &self.kind {
+            ItemKind::Int { val, .. } => Value::Int(*val),
+            ItemKind::Hex { val, .. } => Value::Hex(val.clone()),
+            ItemKind::Text { val, .. } => Value::Text(val.clone()),
+            ItemKind::Choice { val, values, .. } => Value::Choice(values.get(*val).map_or(0, |o| o.val )),
+            ItemKind::Check { val, .. } => Value::Check(*val),
+        }
+    }
+
+    // Checks a value against the kind and limits of the item
+    pub fn check(&self,v: &Value) -> std::result::Result<(),String> {
+        match (&self.kind,v) {
+            (ItemKind::Int { vmin, vmax, .. }, Value::Int(n)) if n < vmin || n > vmax =>
+                Err(format!("{} is out of range {}..{}",n,vmin,vmax)),
+            (ItemKind::Hex { .. }, Value::Hex(h)) if !ConfItem::is_hex(h) =>
+                Err(format!("'{}' is not an even number of hex digits",h)),
+            (ItemKind::Hex { maxlen, .. }, Value::Hex(h)) if h.len() > 2*maxlen =>
+                Err(format!("'{}' is longer than {} bytes",h,maxlen)),
+            (ItemKind::Text { maxlen, .. }, Value::Text(t)) if t.len() > *maxlen =>
+                Err(format!("text is longer than {} characters",maxlen)),
+            (ItemKind::Choice { values, .. }, Value::Choice(c)) if !values.iter().any( |o| o.val == *c ) =>
+                Err(format!("{} is not one of the choices",c)),
+            (ItemKind::Int { .. }, Value::Int(_)) |
+            (ItemKind::Hex { .. }, Value::Hex(_)) |
+            (ItemKind::Text { .. }, Value::Text(_)) |
+            (ItemKind::Choice { .. }, Value::Choice(_)) |
+            (ItemKind::Check { .. }, Value::Check(_)) => Ok(()),
+            _ => Err(format!("wrong kind of value for '{}'",self.id())),
+        }
+    }
+
+    pub fn set(&mut self,v: Value) -> std::result::Result<(),String> {
+        self.check(&v)?;
+        self.value=v;
+        self.kept=None;
+        Ok(())
+    }
+
+    // Restores the spec default, if the item has one
+    pub fn reset(&mut self) {
+        if self.has_default() {
+            self.value=self.default_value();
+            self.kept=None;
+        }
+    }
+
+    pub fn has_default(&self) -> bool {
+        !self.no_default
+    }
+
+    // Typed value of a raw device string, checked against the item limits
+    pub fn parse(&self,raw:&str) -> std::result::Result<Value,String> {
+        let v=self.parse_unchecked(raw)?;
+        self.check(&v)?;
+        Ok(v)
+    }
+
+    // Typed value of a raw device string, whatever the limits of the item
+    pub fn parse_unchecked(&self,raw:&str) -> std::result::Result<Value,String> {
+        let bad=|| format!("'{}' is not a valid value for '{}'",raw,self.id());
+        let v=match &self.kind {
+            ItemKind::Int { .. } => Value::Int(raw.parse().map_err( |_| bad() )?),
+            ItemKind::Hex { lsb, .. } => Value::Hex(if *lsb && ConfItem::is_hex(raw) { ConfItem::invert(raw) } else { raw.into() }),
+            ItemKind::Text { .. } => Value::Text(raw.into()),
+            ItemKind::Choice { .. } => Value::Choice(raw.parse().map_err( |_| bad() )?),
+            ItemKind::Check { .. } => match raw {
+                "1" => Value::Check(true),
+                "0" => Value::Check(false),
+                _ => return Err(bad()),
+            },
+        };
+        Ok(v)
+    }
+
+    // Raw device string of a value
+    pub fn format(&self,v: &Value) -> String {
+        match (&self.kind,v) {
+            (ItemKind::Hex { lsb: true, .. }, Value::Hex(h)) => ConfItem::invert(h),
+            (_, Value::Int(n)) | (_, Value::Choice(n)) => n.to_string(),
+            (_, Value::Hex(t)) | (_, Value::Text(t)) => t.clone(),
+            (_, Value::Check(b)) => if *b { "1" } else { "0" }.into(),
+        }
+    }
+
+    pub fn from_raw(&mut self,raw:&str) -> std::result::Result<(),String> {
+        self.value=self.parse(raw)?;
+        self.kept=None;
+        Ok(())
+    }
+
+    // Like from_raw, but a value the spec rejects is kept as the device sent
+    // it, so writing the config back does not change it
+    pub fn from_device(&mut self,raw:&str) -> std::result::Result<(),String> {
+        let res=self.from_raw(raw);
+        if res.is_err() {
+            self.kept=Some(raw.into());
+        }
+        res
+    }
+
+    // Device value kept by from_device, if the spec rejected it
+    pub fn kept(&self) -> Option<&str> {
+        self.kept.as_deref()
+    }
+
+    // Current value as the device stores it
+    pub fn raw(&self) -> String {
+        self.kept.clone().unwrap_or_else( || self.format(&self.value) )
+    }
+
+    // True if the device would accept `raw` for this item
+    pub fn validate(&self,raw:&str) -> bool {
+        self.parse(raw).is_ok()
+    }
+
+    // Spec default as the device stores it, None for items without one
+    pub fn default_raw(&self) -> Option<String> {
+        if self.has_default() { Some(self.format(&self.default_value())) } else { None }
+    }
+
+    // Raw device value as the user sees it in the editor
+    pub fn describe(&self,raw:&str) -> String {
+        match (&self.kind,self.parse(raw)) {
+            (_, Ok(Value::Check(b))) => if b { "on" } else { "off" }.into(),
+            (_, Ok(Value::Hex(h))) => h,
+            (ItemKind::Choice { values, .. }, Ok(Value::Choice(c))) =>
+                values.iter().find( |o| o.val == c ).map( |o| o.desc.to_string() ).unwrap_or_else( || raw.into() ),
+            _ => raw.into(),
+        }
+    }
+
+    // Command that stores `raw` in the device
+    pub fn to_command(&self,raw:&str) -> String {
+        format!("{} {}",self.id(),raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int() -> ConfItem {
+        ConfItem::from(ItemKind::Int { id: "repo".into(), caption: "Interval".into(), val: 15, vmax: 255, vmin: 1 })
+    }
+
+    fn hex(lsb: bool) -> ConfItem {
+        ConfItem::from(ItemKind::Hex { id: "pipe".into(), caption: "Pipe".into(), val: "AABBCCDDEE".into(), maxlen: 5, lsb })
+    }
+
+    fn text() -> ConfItem {
+        ConfItem::from(ItemKind::Text { id: "tpl".into(), caption: "Template".into(), val: "%Id".into(), maxlen: 8 })
+    }
+
+    fn choice() -> ConfItem {
+        let values=vec!(Choice { val: 0, desc: "Low".into() },Choice { val: 3, desc: "High".into() });
+        ConfItem::from(ItemKind::Choice { id: "txp".into(), caption: "Tx power".into(), val: 1, values })
+    }
+
+    fn check() -> ConfItem {
+        ConfItem::from(ItemKind::Check { id: "led".into(), caption: "Led".into(), val: false })
+    }
+
+    fn device() -> Device {
+        Device {
+            signature: Signature::new("BM","24M","4"),
+            title: "Test".into(),
+            serial: None,
+            release: Release::default(),
+            sections: vec!(Section { name: "General".into(), help: Localized::default(), items: vec!(int(),text(),check()), generated: None }),
+            described: false,
+        }
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        for (mut item,raw) in [(int(),"42"),(hex(false),"0102030405"),(hex(true),"0102030405"),(text(),"%Id,%Td"),(choice(),"0"),(check(),"1")] {
+            item.from_raw(raw).unwrap();
+            assert_eq!(item.raw(),raw,"{}",item.id());
+            assert_eq!(item.parse(&item.format(item.value())).unwrap(),*item.value());
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(int().default_raw().as_deref(),Some("15"));
+        assert_eq!(choice().default_raw().as_deref(),Some("3"));
+        assert_eq!(check().default_raw().as_deref(),Some("0"));
+        assert_eq!(hex(true).default_raw().as_deref(),Some("EEDDCCBBAA"));
+    }
+
+    #[test]
+    fn hex_lsb_swaps_bytes() {
+        let mut item=hex(true);
+        item.from_raw("EEDDCCBBAA").unwrap();
+        assert_eq!(*item.value(),Value::Hex("AABBCCDDEE".into()));
+        assert_eq!(item.format(&Value::Hex("0102".into())),"0201");
+        let mut item=hex(false);
+        item.from_raw("EEDDCCBBAA").unwrap();
+        assert_eq!(*item.value(),Value::Hex("EEDDCCBBAA".into()));
+    }
+
+    #[test]
+    fn hex_limits() {
+        let item=hex(false);
+        assert!(item.parse("ABC").is_err());
+        assert!(item.parse("XY").is_err());
+        assert!(item.parse("010203040506").is_err());
+    }
+
+    #[test]
+    fn int_limits() {
+        let item=int();
+        assert!(item.parse("1").is_ok());
+        assert!(item.parse("255").is_ok());
+        assert!(item.parse("0").is_err());
+        assert!(item.parse("256").is_err());
+        assert!(item.parse("ten").is_err());
+    }
+
+    #[test]
+    fn choice_limits() {
+        let item=choice();
+        assert_eq!(item.parse("3").unwrap(),Value::Choice(3));
+        assert!(item.parse("1").is_err());
+        assert!(item.parse("high").is_err());
+        assert_eq!(item.describe("3"),"High");
+    }
+
+    #[test]
+    fn generic_items_have_no_default() {
+        let entries=[ConfigEntry { id: "rate".into(), label: "Sample rate".into(), raw_value: "10".into() }];
+        let mut dev=Device::generic(&Signature::new("XX","1","1"),&entries);
+        let item=dev.find("rate").unwrap();
+        assert!(!item.has_default());
+        assert_eq!(item.default_raw(),None);
+        dev.apply(&[("rate".into(),"20".into())]);
+        dev.reset();
+        assert_eq!(dev.find("rate").unwrap().raw(),"20");
+    }
+
+    #[test]
+    fn text_and_check_limits() {
+        assert!(text().parse("123456789").is_err());
+        assert!(check().parse("2").is_err());
+        assert!(int().check(&Value::Text("1".into())).is_err());
+    }
+
+    #[test]
+    fn rejected_device_value_is_kept() {
+        let mut item=int();
+        assert!(item.from_device("300").is_err());
+        assert_eq!(item.kept(),Some("300"));
+        assert_eq!(item.raw(),"300");
+        item.set(Value::Int(20)).unwrap();
+        assert_eq!(item.kept(),None);
+        assert_eq!(item.raw(),"20");
+    }
+
+    #[test]
+    fn apply_rejects_unknown_and_invalid() {
+        let mut dev=device();
+        let rejected=dev.apply(&[("repo".into(),"30".into()),("nope".into(),"1".into()),("led".into(),"5".into())]);
+        assert_eq!(rejected.len(),2);
+        assert!(rejected[0].starts_with("nope:"));
+        assert!(rejected[1].starts_with("led:"));
+        assert_eq!(dev.find("repo").unwrap().raw(),"30");
+        assert_eq!(dev.find("led").unwrap().raw(),"0");
+        assert_eq!(dev.values()[0],("repo".to_string(),"30".to_string()));
+    }
+
+    fn version(v: &str,migrate: Vec<Rule>) -> Device {
+        let mut dev=device();
+        dev.signature=Signature::new("BM","24M",v);
+        dev.release.migrate=migrate;
+        dev
+    }
+
+    fn migration(product: &str,from: i32,to: i32,rules: Vec<Rule>) -> Migration {
+        Migration { product: product.into(), model: "24M".into(), from, to, rules }
+    }
+
+    fn ids(rules: &[Rule]) -> Vec<String> {
+        rules.iter().map( |r| match r {
+            Rule::Remove { id } | Rule::Set { id, .. } | Rule::Map { id, .. } | Rule::Scale { id, .. } => id.clone(),
+            Rule::Rename { from, .. } => from.clone(),
+        }).collect()
+    }
+
+    fn remove(id: &str) -> Rule {
+        Rule::Remove { id: id.into() }
+    }
+
+    #[test]
+    fn upgrade_chains_the_longest_steps() {
+        let root=Root {
+            spec: ["4","5","6","7","8"].iter().map( |v| version(v,vec!()) ).collect(),
+            migrations: vec!(
+                migration("BM",4,5,vec!(remove("a"))),
+                migration("BM",5,6,vec!(remove("b"))),
+                migration("BM",5,7,vec!(remove("c"),remove("d"))),
+                migration("BX",7,8,vec!(remove("e"))),
+            ),
+        };
+        let from=Signature::new("BM","24M","4");
+        assert_eq!(ids(&root.upgrade_rules(&from,&Signature::new("BM","24M","8"))),vec!("a","c","d"));
+        assert_eq!(ids(&root.upgrade_rules(&from,&Signature::new("BM","24M","6"))),vec!("a","b"));
+        assert!(root.upgrade_rules(&Signature::new("BM","24M","7"),&Signature::new("BM","24M","8")).is_empty());
+    }
+
+    #[test]
+    fn upgrade_falls_back_to_release_rules() {
+        let root=Root {
+            spec: vec!(version("4",vec!()),version("5",vec!(remove("a"))),version("6",vec!()),version("7",vec!(remove("b")))),
+            migrations: vec!(migration("BM",6,7,vec!(remove("c")))),
+        };
+        // The top level entry wins over the release of the same version
+        assert_eq!(ids(&root.upgrade_rules(&Signature::new("BM","24M","4"),&Signature::new("BM","24M","7"))),vec!("a","c"));
+        assert_eq!(ids(&root.upgrade_rules(&Signature::new("BM","24M","4"),&Signature::new("BM","24M","5"))),vec!("a"));
+    }
+}
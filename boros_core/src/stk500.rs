@@ -0,0 +1,277 @@
+use std::io::{Result,Error,ErrorKind};
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::ihex::Image;
+use crate::ser::{Transport,pulse_reset};
+use crate::settings::SerialSettings;
+
+// STK500v1 subset spoken by the Arduino (optiboot/ATmegaBOOT) bootloader
+const STK_OK: u8 = 0x10;
+const STK_INSYNC: u8 = 0x14;
+#[cfg(test)]
+const STK_NOSYNC: u8 = 0x15;
+const CRC_EOP: u8 = 0x20;
+const STK_GET_SYNC: u8 = 0x30;
+const STK_ENTER_PROGMODE: u8 = 0x50;
+const STK_LEAVE_PROGMODE: u8 = 0x51;
+const STK_LOAD_ADDRESS: u8 = 0x55;
+const STK_PROG_PAGE: u8 = 0x64;
+const STK_READ_PAGE: u8 = 0x74;
+const STK_READ_SIGN: u8 = 0x75;
+
+const PAGE_SIZE: usize = 128;
+const SYNC_ATTEMPTS: usize = 10;
+
+fn protocol(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData,msg)
+}
+
+struct Programmer<'a> {
+    port: &'a mut dyn Transport,
+}
+
+impl<'a> Programmer<'a> {
+    fn cmd(&mut self, req: &[u8], answer_len: usize) -> Result<Vec<u8>> {
+        let mut out=req.to_vec();
+        out.push(CRC_EOP);
+        self.port.write_all(&out)?;
+        let mut b=[0u8;1];
+        self.port.read_exact(&mut b)?;
+        if b[0] != STK_INSYNC {
+            return Err(protocol(format!("bootloader out of sync (0x{:02x}) on command 0x{:02x}",b[0],req[0])));
+        }
+        let mut data=vec![0u8;answer_len];
+        self.port.read_exact(&mut data)?;
+        self.port.read_exact(&mut b)?;
+        if b[0] != STK_OK {
+            return Err(protocol(format!("bootloader rejected command 0x{:02x}",req[0])));
+        }
+        Ok(data)
+    }
+
+    // Throws away whatever the application printed before the reset took effect
+    fn drain(&mut self) {
+        let mut b=[0u8;64];
+        while let Ok(n)=self.port.read(&mut b) {
+            if n == 0 { break }
+        }
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        for _ in 0..SYNC_ATTEMPTS {
+            if self.cmd(&[STK_GET_SYNC],0).is_ok() {
+                return Ok(());
+            }
+            self.drain();
+        }
+        Err(Error::new(ErrorKind::TimedOut,"no answer from the bootloader"))
+    }
+
+    // The address is in 16 bit words, so 128 KiB at most
+    fn load_address(&mut self, addr: usize) -> Result<()> {
+        let w=addr / 2;
+        if w > 0xffff {
+            return Err(Error::new(ErrorKind::InvalidInput,format!("address {:#x} is beyond the 128 KiB the bootloader can reach",addr)));
+        }
+        self.cmd(&[STK_LOAD_ADDRESS,(w & 0xff) as u8,(w >> 8) as u8],0).map(|_| ())
+    }
+
+    fn prog_page(&mut self, data: &[u8]) -> Result<()> {
+        let mut req=vec![STK_PROG_PAGE,(data.len() >> 8) as u8,(data.len() & 0xff) as u8,b'F'];
+        req.extend_from_slice(data);
+        self.cmd(&req,0).map(|_| ())
+    }
+
+    fn read_page(&mut self, len: usize) -> Result<Vec<u8>> {
+        self.cmd(&[STK_READ_PAGE,(len >> 8) as u8,(len & 0xff) as u8,b'F'],len)
+    }
+
+    fn run(&mut self, image: &Image, progress: &mut dyn FnMut(&str,usize,usize)) -> Result<[u8;3]> {
+        self.sync()?;
+        let sig=self.cmd(&[STK_READ_SIGN],3)?;
+        self.cmd(&[STK_ENTER_PROGMODE],0)?;
+
+        // Page aligned copy of the image
+        let base=image.base - image.base % PAGE_SIZE;
+        let mut data=vec![0xFFu8; image.base - base];
+        data.extend_from_slice(&image.data);
+        let pages: Vec<&[u8]>=data.chunks(PAGE_SIZE).collect();
+
+        for (n,page) in pages.iter().enumerate() {
+            self.load_address(base + n*PAGE_SIZE)?;
+            self.prog_page(page)?;
+            progress("Writing",n+1,pages.len());
+        }
+        for (n,page) in pages.iter().enumerate() {
+            let addr=base + n*PAGE_SIZE;
+            self.load_address(addr)?;
+            if self.read_page(page.len())? != *page {
+                return Err(protocol(format!("verification failed at 0x{:04x}",addr)));
+            }
+            progress("Verifying",n+1,pages.len());
+        }
+        self.cmd(&[STK_LEAVE_PROGMODE],0)?;
+        Ok([sig[0],sig[1],sig[2]])
+    }
+}
+
+// Resets the device into its bootloader, writes and verifies the image.
+// Images past `line.flash_size` are refused before the reset. Returns the
+// MCU signature bytes reported by the bootloader.
+pub fn program(port: &mut dyn Transport, line: &SerialSettings, image: &Image,
+               progress: &mut dyn FnMut(&str,usize,usize)) -> Result<[u8;3]> {
+    if image.base + image.data.len() > line.flash_size {
+        return Err(Error::new(ErrorKind::InvalidInput,format!("image of {} bytes at {:#x} does not fit in {} bytes of flash",image.data.len(),image.base,line.flash_size)));
+    }
+    port.set_baud_rate(line.bootloader_baud)?;
+    port.set_timeout(Duration::from_millis(300))?;
+    pulse_reset(port,line);
+    sleep(Duration::from_millis(50));
+    let res=Programmer { port: &mut *port }.run(image,progress);
+    let _=port.set_baud_rate(line.baud);
+    let _=port.set_timeout(Duration::from_secs(2));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read,Write};
+    use crate::replay::Replay;
+
+    // 160 bytes at 0x0000: one full page and part of the next
+    const FIXTURE: &str = "\
+:10000000030A11181F262D343B424950575E656C78
+:10001000737A81888F969DA4ABB2B9C0C7CED5DC68
+:10002000E3EAF1F8FF060D141B222930373E454C58
+:10003000535A61686F767D848B9299A0A7AEB5BC48
+:10004000C3CAD1D8DFE6EDF4FB020910171E252C38
+:10005000333A41484F565D646B727980878E959C28
+:10006000A3AAB1B8BFC6CDD4DBE2E9F0F7FE050C18
+:10007000131A21282F363D444B525960676E757C08
+:10008000838A91989FA6ADB4BBC2C9D0D7DEE5ECF8
+:10009000F3FA01080F161D242B323940474E555CE8
+:00000001FF
+";
+    const FLASH_SIZE: usize = 32*1024;
+
+    // Minimal STK500v1 bootloader used to exercise the programmer without hardware
+    struct Bootloader {
+        flash: Vec<u8>,
+        signature: [u8;3],
+        addr: usize,
+    }
+
+    impl Bootloader {
+        fn new(size: usize) -> Self {
+            Self { flash: vec![0xFFu8; size], signature: [0x1e,0x95,0x0f], addr: 0 }
+        }
+
+        fn read_n<T: Read>(io: &mut T, n: usize) -> Result<Vec<u8>> {
+            let mut b=vec![0u8;n];
+            io.read_exact(&mut b)?;
+            Ok(b)
+        }
+
+        fn check_page(&self, len: usize) -> Result<()> {
+            if self.addr + len > self.flash.len() {
+                return Err(protocol(format!("page at {:#x} is past the {} bytes of flash",self.addr,self.flash.len())));
+            }
+            Ok(())
+        }
+
+        // Serves requests until the host leaves programming mode or the link fails
+        fn serve<T: Read + Write>(&mut self, io: &mut T) -> Result<()> {
+            loop {
+                let c=Bootloader::read_n(io,1)?[0];
+                let answer=match c {
+                    STK_READ_SIGN => self.signature.to_vec(),
+                    STK_LOAD_ADDRESS => {
+                        let a=Bootloader::read_n(io,2)?;
+                        self.addr=((a[0] as usize) | ((a[1] as usize) << 8)) * 2;
+                        vec!()
+                    },
+                    STK_PROG_PAGE => {
+                        let h=Bootloader::read_n(io,3)?;
+                        let len=((h[0] as usize) << 8) | h[1] as usize;
+                        let data=Bootloader::read_n(io,len)?;
+                        self.check_page(len)?;
+                        self.flash[self.addr..self.addr+len].copy_from_slice(&data);
+                        vec!()
+                    },
+                    STK_READ_PAGE => {
+                        let h=Bootloader::read_n(io,3)?;
+                        let len=((h[0] as usize) << 8) | h[1] as usize;
+                        self.check_page(len)?;
+                        self.flash[self.addr..self.addr+len].to_vec()
+                    },
+                    CRC_EOP => continue,
+                    _ => vec!(),
+                };
+                if Bootloader::read_n(io,1)?[0] != CRC_EOP {
+                    io.write_all(&[STK_NOSYNC])?;
+                    continue;
+                }
+                let mut out=vec![STK_INSYNC];
+                out.extend_from_slice(&answer);
+                out.push(STK_OK);
+                io.write_all(&out)?;
+                io.flush()?;
+                if c == STK_LEAVE_PROGMODE {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // Flashes `image` into a simulated bootloader on the other end of a PTY
+    // pair, returns the signature read and the simulated flash
+    #[cfg(unix)]
+    fn flash_simulated(image: &Image) -> Result<([u8;3],Vec<u8>)> {
+        use serialport::SerialPort;
+        use serialport::posix::TTYPort;
+
+        let (master,mut slave)=TTYPort::pair().map_err(Error::from)?;
+        slave.set_timeout(Duration::from_secs(5)).map_err(Error::from)?;
+        let sim=std::thread::spawn(move || {
+            let mut boot=Bootloader::new(FLASH_SIZE);
+            let r=boot.serve(&mut slave);
+            (r,boot)
+        });
+
+        let mut port: Box<dyn SerialPort>=Box::new(master);
+        let line=SerialSettings { flash_size: FLASH_SIZE, ..SerialSettings::default() };
+        let sig=program(&mut port,&line,image,&mut |_,_,_| {})?;
+        let (served,boot)=sim.join().map_err( |_| Error::new(ErrorKind::Other,"bootloader thread panicked") )?;
+        served?;
+        Ok((sig,boot.flash))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn program_simulated() {
+        let image=Image::parse(FIXTURE,FLASH_SIZE).unwrap();
+        assert_eq!(image.data.len(),160);
+        let (sig,flash)=flash_simulated(&image).unwrap();
+        assert_eq!(sig,Bootloader::new(0).signature);
+        assert_eq!(&flash[..160],&image.data[..]);
+        assert!(flash[160..].iter().all( |b| *b == 0xFF ));
+    }
+
+    // Refused before the device is reset: nothing is sent
+    #[test]
+    fn image_larger_than_flash() {
+        let image=Image { base: 0x7f00, data: vec![0u8;512] };
+        let line=SerialSettings { flash_size: FLASH_SIZE, ..SerialSettings::default() };
+        let mut port=Replay::from_transcript("");
+        assert_eq!(program(&mut port,&line,&image,&mut |_,_,_| {}).unwrap_err().kind(),ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn address_beyond_128k() {
+        let mut port=Replay::from_transcript("");
+        let mut p=Programmer { port: &mut port };
+        assert_eq!(p.load_address(0x20000).unwrap_err().kind(),ErrorKind::InvalidInput);
+    }
+}
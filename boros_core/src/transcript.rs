@@ -0,0 +1,119 @@
+use std::fs::{File,OpenOptions};
+use std::io::{Write,Result};
+use std::path::Path;
+use std::time::{SystemTime,UNIX_EPOCH};
+
+// Transcript line format:  <timestamp> <marker> <escaped payload>
+//   >>  bytes sent to the device
+//   <<  bytes received from the device
+//   --  control events (line resets, notes)
+pub const TX: &str = ">>";
+pub const RX: &str = "<<";
+pub const CTL: &str = "--";
+
+pub struct Transcript {
+    file: File,
+}
+
+impl Transcript {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file=OpenOptions::new().create(true).append(true).open(path)?;
+        let mut t=Self { file };
+        t.note("transcript started");
+        Ok(t)
+    }
+
+    pub fn sent(&mut self, data: &[u8]) {
+        self.entry(TX,&escape(data));
+    }
+
+    pub fn received(&mut self, data: &[u8]) {
+        if !data.is_empty() {
+            self.entry(RX,&escape(data));
+        }
+    }
+
+    pub fn note(&mut self, msg: &str) {
+        self.entry(CTL,msg);
+    }
+
+    fn entry(&mut self, marker: &str, payload: &str) {
+        let _=writeln!(self.file,"{} {} {}",timestamp(),marker,payload);
+        let _=self.file.flush();
+    }
+}
+
+// ISO-8601 UTC timestamp with milliseconds, e.g. 2020-11-02T17:04:11.532Z
+pub fn timestamp() -> String {
+    let d=SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs=d.as_secs();
+    let (y,m,day)=civil_from_days((secs / 86400) as i64);
+    let s=secs % 86400;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",y,m,day,s/3600,(s%3600)/60,s%60,d.subsec_millis())
+}
+
+// Days since 1970-01-01 to (year,month,day). H. Hinnant's civil_from_days.
+fn civil_from_days(z: i64) -> (i64,u32,u32) {
+    let z=z+719468;
+    let era=(if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe=z - era * 146097;
+    let yoe=(doe - doe/1460 + doe/36524 - doe/146096) / 365;
+    let doy=doe - (365*yoe + yoe/4 - yoe/100);
+    let mp=(5*doy + 2)/153;
+    let d=(doy - (153*mp+2)/5 + 1) as u32;
+    let m=(if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let y=yoe + era * 400 + (if m <= 2 { 1 } else { 0 });
+    (y,m,d)
+}
+
+pub fn escape(data: &[u8]) -> String {
+    let mut s=String::with_capacity(data.len());
+    for b in data {
+        match *b {
+            b'\n' => s.push_str("\\n"),
+            b'\r' => s.push_str("\\r"),
+            b'\t' => s.push_str("\\t"),
+            b'\\' => s.push_str("\\\\"),
+            0x20..=0x7e => s.push(*b as char),
+            _ => s.push_str(&format!("\\x{:02x}",b)),
+        }
+    }
+    s
+}
+
+pub fn unescape(s: &str) -> Vec<u8> {
+    let mut out=Vec::with_capacity(s.len());
+    let mut chars=s.chars();
+    while let Some(c)=chars.next() {
+        if c != '\\' {
+            let mut b=[0u8;4];
+            out.extend_from_slice(c.encode_utf8(&mut b).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push(b'\n'),
+            Some('r') => out.push(b'\r'),
+            Some('t') => out.push(b'\t'),
+            Some('x') => {
+                let h: String=chars.by_ref().take(2).collect();
+                out.push(u8::from_str_radix(&h,16).unwrap_or(b'?'));
+            },
+            Some(o) => out.push(o as u8),
+            None => out.push(b'\\'),
+        }
+    }
+    out
+}
+
+// Splits a transcript line into its marker and decoded payload
+pub fn parse_line(l: &str) -> Option<(&str,Vec<u8>)> {
+    let mut parts=l.splitn(3,' ');
+    let _stamp=parts.next()?;
+    let marker=parts.next()?;
+    let payload=parts.next().unwrap_or("");
+    match marker {
+        TX | RX => Some((marker,unescape(payload))),
+        CTL => Some((marker,payload.as_bytes().to_vec())),
+        _ => None,
+    }
+}
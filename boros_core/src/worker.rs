@@ -0,0 +1,54 @@
+use std::sync::mpsc::{channel,Sender};
+use std::thread::JoinHandle;
+
+use crate::ser::BorosSerial;
+
+type Job=Box<dyn FnOnce(&mut BorosSerial) + Send>;
+
+// Owns the serial connection of one device on its own thread, so several
+// devices can be talked to at once. Jobs run in the order they are queued.
+pub struct Worker {
+    jobs: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    pub fn spawn(mut ser: BorosSerial) -> Self {
+        let (jobs,queue)=channel::<Job>();
+        let thread=std::thread::spawn(move || {
+            for job in queue {
+                job(&mut ser);
+            }
+        });
+        Self { jobs: Some(jobs), thread: Some(thread) }
+    }
+
+    // Queues a job; results are reported by the job itself. False if the worker is gone.
+    pub fn post<F>(&self,job: F) -> bool
+        where F: FnOnce(&mut BorosSerial) + Send + 'static {
+        match &self.jobs {
+            Some(j) => j.send(Box::new(job)).is_ok(),
+            None => false,
+        }
+    }
+
+    // Runs a job and waits for its result. None if the worker is gone.
+    pub fn call<R,F>(&self,job: F) -> Option<R>
+        where R: Send + 'static, F: FnOnce(&mut BorosSerial) -> R + Send + 'static {
+        let (tx,rx)=channel();
+        if !self.post(move |ser| { let _=tx.send(job(ser)); }) {
+            return None;
+        }
+        rx.recv().ok()
+    }
+}
+
+// Waits for the queued jobs so the port is closed when the worker is dropped
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.jobs=None;
+        if let Some(t)=self.thread.take() {
+            let _=t.join();
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use boros_core::detect::{UsbFilter,parse_hex_id};
+use boros_core::settings::SerialOverride;
+use boros_core::ser::ConnectMode;
+
+pub const USAGE: &str = "Usage: boros_gui [options]
+Options:
+  -l, --log <file>    Write a timestamped transcript of the serial protocol to <file>
+  --replay <file>     Replay a recorded transcript against the protocol code and exit
+                      (may be repeated; exit status is 1 on any mismatch)
+  --validate <file>   Check a saved configuration file against the spec and exit
+                      (may be repeated; exit status is 1 on any invalid value)
+  --attach            Attach to a running device without resetting it
+  --no-attach         Reset the device on connect, even if the preferences attach
+  --detect            Probe serial ports for Boros devices, list them and exit
+  --detect-all        Like --detect, also listing devices missing from the spec
+  --vid <hex>         Only consider USB ports with this vendor id (detection)
+  --pid <hex>         Only consider USB ports with this product id (detection)
+Profiles:
+  --profile <name>    Apply the profile to the editor after the first read
+  --profiles          List the stored profiles and exit
+  --profile-show <name>
+                      Print the values of a profile and exit
+  --profile-delete <name>
+                      Delete a profile and exit
+  --profile-import <file>
+                      Store a saved configuration or profile file as a profile and exit
+Serial line (overrides the spec of the device):
+  --baud <n>          Baud rate
+  --data-bits <5-8>   Data bits
+  --parity <p>        none, odd or even
+  --stop-bits <1|2>   Stop bits
+  --flow <f>          none, software or hardware flow control
+  --reset <line>      Line pulsed to reset the device: dtr, rts or none
+  --reset-pulse <ms>  Reset pulse width
+  --boot-delay <ms>   Time to wait after reset before expecting the prompt
+  --bootloader-baud <n>
+                      Baud rate of the bootloader used to flash firmware
+  -h, --help          Show this help";
+
+pub enum ProfileCmd {
+    List,
+    Show(String),
+    Delete(String),
+    Import(PathBuf),
+}
+
+#[derive(Default)]
+pub struct Options {
+    pub transcript: Option<PathBuf>,
+    pub replay: Vec<PathBuf>,
+    pub validate: Vec<PathBuf>,
+    pub detect: bool,
+    // --detect-all: also list devices missing from the spec
+    pub detect_all: bool,
+    pub usb_filter: UsbFilter,
+    pub serial: SerialOverride,
+    // None keeps the preferences choice
+    pub mode: Option<ConnectMode>,
+    pub profile: Option<String>,
+    pub profile_cmd: Option<ProfileCmd>,
+}
+
+impl Options {
+    pub fn parse() -> Result<Self,String> {
+        let mut opts=Options::default();
+        let mut args=std::env::args().skip(1);
+        while let Some(a)=args.next() {
+            match a.as_str() {
+                "-l" | "--log" => {
+                    let f=args.next().ok_or_else(|| format!("'{}' requires a file name",a))?;
+                    opts.transcript=Some(PathBuf::from(f));
+                },
+                "--replay" => {
+                    let f=args.next().ok_or_else(|| format!("'{}' requires a file name",a))?;
+                    opts.replay.push(PathBuf::from(f));
+                },
+                "--validate" => {
+                    let f=args.next().ok_or_else(|| format!("'{}' requires a file name",a))?;
+                    opts.validate.push(PathBuf::from(f));
+                },
+                "--profile" => {
+                    opts.profile=Some(args.next().ok_or_else(|| format!("'{}' requires a profile name",a))?);
+                },
+                "--profiles" => opts.profile_cmd=Some(ProfileCmd::List),
+                "--profile-show" | "--profile-delete" => {
+                    let n=args.next().ok_or_else(|| format!("'{}' requires a profile name",a))?;
+                    opts.profile_cmd=Some(if a == "--profile-show" { ProfileCmd::Show(n) } else { ProfileCmd::Delete(n) });
+                },
+                "--profile-import" => {
+                    let f=args.next().ok_or_else(|| format!("'{}' requires a file name",a))?;
+                    opts.profile_cmd=Some(ProfileCmd::Import(PathBuf::from(f)));
+                },
+                "--detect" => opts.detect=true,
+                "--detect-all" => {
+                    opts.detect=true;
+                    opts.detect_all=true;
+                },
+                "--attach" => opts.mode=Some(ConnectMode::Attach),
+                "--no-attach" => opts.mode=Some(ConnectMode::Reset),
+                "--vid" | "--pid" => {
+                    let v=args.next().ok_or_else(|| format!("'{}' requires a hex id",a))?;
+                    let id=Some(parse_hex_id(&v)?);
+                    if a == "--vid" { opts.usb_filter.vid=id } else { opts.usb_filter.pid=id }
+                },
+                "--baud" | "--data-bits" | "--parity" | "--stop-bits" | "--flow" |
+                "--reset" | "--reset-pulse" | "--boot-delay" | "--bootloader-baud" => {
+                    let v=args.next().ok_or_else(|| format!("'{}' requires a value",a))?;
+                    opts.serial.parse_arg(&a[2..],&v)?;
+                },
+                "-h" | "--help" => {
+                    println!("{}",USAGE);
+                    std::process::exit(0);
+                },
+                _ => return Err(format!("Unknown option '{}'",a)),
+            }
+        }
+        Ok(opts)
+    }
+}
@@ -0,0 +1,29 @@
+use std::io::Write;
+use std::process::{Command,Stdio};
+
+// libui has no clipboard API, so the text is piped to the copy tool of the
+// platform. On Linux the first tool found wins (Wayland, then X11).
+#[cfg(target_os = "windows")]
+const TOOLS: &[&[&str]] = &[&["clip"]];
+#[cfg(target_os = "macos")]
+const TOOLS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const TOOLS: &[&[&str]] = &[&["wl-copy"], &["xclip","-selection","clipboard"], &["xsel","--clipboard","--input"]];
+
+fn pipe(tool: &[&str], text: &str) -> std::io::Result<bool> {
+    let mut child=Command::new(tool[0]).args(&tool[1..]).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin)=child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    Ok(child.wait()?.success())
+}
+
+pub fn copy(text: &str) -> Result<(),String> {
+    for tool in TOOLS {
+        if let Ok(true)=pipe(tool,text) {
+            return Ok(());
+        }
+    }
+    let names: Vec<&str>=TOOLS.iter().map( |t| t[0] ).collect();
+    Err(format!("No clipboard tool found (tried {})",names.join(", ")))
+}
@@ -0,0 +1,106 @@
+use std::sync::mpsc::Sender;
+
+use iui::prelude::*;
+use iui::controls::{Combobox,Group,Label,Button,VerticalBox,HorizontalBox};
+
+use crate::Actions;
+use crate::i18n::tr;
+
+// Open devices in the port window, with the actions working across editors.
+// As in PortList the content is rebuilt into the group when the set changes.
+pub struct DeviceList {
+    ui: UI,
+    group: Group,
+    cmd: Sender<Actions>,
+}
+
+fn combo(ui: &UI, entries: &[(usize,String)], selected: i32) -> Combobox {
+    let mut c=Combobox::new(ui);
+    for (_,text) in entries {
+        c.append(ui,text);
+    }
+    if !entries.is_empty() {
+        c.set_selected(ui,selected.min(entries.len() as i32 - 1));
+    }
+    c
+}
+
+impl DeviceList {
+    pub fn new(ui: &UI, cmd: Sender<Actions>) -> Self {
+        let group=Group::new(ui,tr("Open devices"));
+        let mut dl=Self { ui: ui.clone(), group, cmd };
+        dl.update(&[]);
+        dl
+    }
+
+    pub fn group(&self) -> Group {
+        self.group.clone()
+    }
+
+    // `entries` holds the editor id and description of every open device
+    pub fn update(&mut self, entries: &[(usize,String)]) {
+        let ui=&self.ui;
+        let mut vbox=VerticalBox::new(ui);
+        vbox.set_padded(ui,true);
+        if entries.is_empty() {
+            vbox.append(ui,Label::new(ui,tr("No device open")),LayoutStrategy::Compact);
+            self.group.set_child(ui,vbox);
+            return;
+        }
+        let ids: Vec<usize>=entries.iter().map( |e| e.0 ).collect();
+        let from=combo(ui,entries,0);
+        let to=combo(ui,entries,1);
+
+        let mut show=Button::new(ui,tr("Show"));
+        show.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let from=from.clone();
+            let ids=ids.clone();
+            move |_| {
+                if let Some(id)=ids.get(from.selected(&ui) as usize) {
+                    let _=c.send(Actions::ShowEditor(*id));
+                }
+            }
+        });
+        let mut close=Button::new(ui,tr("Close"));
+        close.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let from=from.clone();
+            let ids=ids.clone();
+            move |_| {
+                if let Some(id)=ids.get(from.selected(&ui) as usize) {
+                    let _=c.send(Actions::CloseEditor(*id));
+                }
+            }
+        });
+        let mut copy=Button::new(ui,tr("Copy configuration to"));
+        copy.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let from=from.clone();
+            let to=to.clone();
+            move |_| {
+                let a=ids.get(from.selected(&ui) as usize);
+                let b=ids.get(to.selected(&ui) as usize);
+                if let (Some(a),Some(b))=(a,b) {
+                    let _=c.send(Actions::CopyConfig(*a,*b));
+                }
+            }
+        });
+
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,from,LayoutStrategy::Stretchy);
+        hb.append(ui,show,LayoutStrategy::Compact);
+        hb.append(ui,close,LayoutStrategy::Compact);
+        vbox.append(ui,hb,LayoutStrategy::Compact);
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,copy,LayoutStrategy::Compact);
+        hb.append(ui,to,LayoutStrategy::Stretchy);
+        vbox.append(ui,hb,LayoutStrategy::Compact);
+        self.group.set_child(ui,vbox);
+    }
+}
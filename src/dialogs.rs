@@ -0,0 +1,44 @@
+use iui::prelude::*;
+use iui::controls::{Window,Label,Button,VerticalBox,HorizontalBox,Spacer};
+
+use crate::i18n::tr;
+
+// Yes/No question in its own window, libui has no such modal dialog.
+// `on_yes` runs on the GUI thread after the window is hidden.
+pub fn confirm<F: FnMut() + 'static>(ui: &UI, title: &str, question: &str, on_yes: F) {
+    let mut win=Window::new(ui,title,360,120,WindowType::NoMenubar);
+    let mut vbox=VerticalBox::new(ui);
+    vbox.set_padded(ui,true);
+    vbox.append(ui,Label::new(ui,question),LayoutStrategy::Stretchy);
+
+    let mut bbox=HorizontalBox::new(ui);
+    bbox.set_padded(ui,true);
+    let mut no=Button::new(ui,tr("No"));
+    let mut yes=Button::new(ui,tr("Yes"));
+    no.on_clicked(ui, {
+        let ui=ui.clone();
+        let mut w=win.clone();
+        move |_| w.hide(&ui)
+    });
+    yes.on_clicked(ui, {
+        let ui=ui.clone();
+        let mut w=win.clone();
+        let mut f=on_yes;
+        move |_| {
+            w.hide(&ui);
+            f();
+        }
+    });
+    bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+    bbox.append(ui,no,LayoutStrategy::Compact);
+    bbox.append(ui,yes,LayoutStrategy::Compact);
+    vbox.append(ui,bbox,LayoutStrategy::Compact);
+
+    win.on_closing(ui, {
+        let ui=ui.clone();
+        move |w: &mut Window| w.hide(&ui)
+    });
+    win.set_margined(ui,true);
+    win.set_child(ui,vbox);
+    win.show(ui);
+}
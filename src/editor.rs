@@ -1,369 +1,1412 @@
-use serde::Deserialize;
 use iui::prelude::*;
-use iui::controls::{Control, Spinbox,Entry,Combobox,Checkbox,
+use iui::controls::{Control, Spinbox,Entry,Combobox,Checkbox,MultilineEntry,
                     VerticalBox,HorizontalBox,Window,Label,
                     HorizontalSeparator,TabGroup, Button, Spacer };
 
-use crate::ser::BorosSerial;
-use crate::Actions;
-use crate::devices::yml;
-use serde_yaml::{Result,from_str};
-use regex::Regex;
-
-use std::sync::mpsc::Sender;
-
-
+use boros_core::spec::{Root,Device,ConfItem,ItemKind,Value,Signature,DeviceInfo,ConfigEntry,version_at_least};
+use boros_core::ser::{BorosSerial,ConnectMode,CmdStats};
+use boros_core::worker::Worker;
+use boros_core::transcript::{self,Transcript};
+use boros_core::settings::Timeouts;
+use boros_core::ihex::Image;
+use boros_core::migrate::{self,Report};
+use boros_core::snapshot::ConfigFile;
+use boros_core::history::{self,History,Snapshot};
+use boros_core::drift::Drift;
+use boros_core::profiles::{Profile,Profiles};
+use crate::prefs::Prefs;
+use crate::dialogs;
+use crate::ports;
+use crate::clipboard;
+use crate::reports;
+use crate::i18n::{tr,trf};
+use crate::{Actions,Opened};
+use std::sync::mpsc::{channel,Sender,Receiver};
+use std::path::{Path,PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
 
+pub fn gui_supports(min_gui: &str) -> bool {
+    version_at_least(env!("CARGO_PKG_VERSION"),min_gui)
+}
 
-#[derive(Deserialize)]
-pub struct Root {
-    spec: Vec<Device>,
+// iui control showing the value of a spec item
+enum Ctl {
+    Spin(Spinbox),
+    Line(Entry),
+    Combo(Combobox),
+    Check(Checkbox),
 }
 
-#[derive(Deserialize)]
-pub struct Device {
-    signature: Signature,
-    title: String,
-    sections: Vec<Section>,
+struct Widget {
+    ctl: Ctl,
+    // Marks a device value outside the spec, kept as read
+    flag: Label,
+    // Control value shown for that kept value, to notice when the user changes it
+    shown: Option<Value>,
 }
 
-impl Device {
-    pub fn find(&mut self, id: &str) -> Option<&mut ConfItem> {
-        for s in &mut self.sections {
-            let f=s.find(id);
-            if f.is_some() { return f }
+impl Widget {
+    fn build(item: &ConfItem,ui: &UI,cmd: &Sender<Actions>) -> (Self,HorizontalBox) {
+        let mut hb=HorizontalBox::new(ui);
+        let (caption,ctl,control) : (&str,Ctl,Control) = match &item.kind {
+            ItemKind::Text { caption: cap, .. } |
+            ItemKind::Hex { caption: cap, .. } => {
+                let con = Entry::new(ui);
+                (cap.get(), Ctl::Line(con.clone()), con.into())
+            },
+            ItemKind::Int { vmax:vma, vmin: vmi ,caption:cap, ..} => {
+                let con= Spinbox::new(ui,*vmi,*vma);
+                (cap.get(), Ctl::Spin(con.clone()), con.into())
+            },
+            ItemKind::Check { caption: cap, ..} => {
+                let con = Checkbox::new(ui,cap.get());
+                ("", Ctl::Check(con.clone()), con.into())
+            },
+            ItemKind::Choice { caption:cap, values: vals,.. } => {
+                let con = Combobox::new(ui);
+                for v in vals {
+                    con.append(ui,v.desc.get());
+                }
+                (cap.get(), Ctl::Combo(con.clone()), con.into())
+            },
+        };
+
+        if caption != ""  {
+            hb.append(ui,Label::new(ui,caption),LayoutStrategy::Compact);
         }
-        None
+        hb.append(ui,control,LayoutStrategy::Stretchy);
+        let flag=Label::new(ui,"");
+        hb.append(ui,flag.clone(),LayoutStrategy::Compact);
+        if item.has_default() {
+            let mut def=Button::new(ui,tr("Default"));
+            def.on_clicked(ui, {
+                let c=cmd.clone();
+                let id=item.id().to_string();
+                move |_| {
+                    let _=c.send(Actions::DefaultItem(id.clone()));
+                }
+            });
+            hb.append(ui,def,LayoutStrategy::Compact);
+        }
+        hb.set_padded(ui,true);
+        let mut w=Widget { ctl, flag, shown: None };
+        w.load(item,ui);
+        (w,hb)
     }
-}
 
-#[derive(Deserialize,Debug)]
-pub struct Signature {
-    product: String,
-    model: String,
-    version: i32,
-}
-impl Signature {
-    pub fn new(p:&str,m:&str,v:&str) -> Self {
-        let v = v.parse::<i32>().unwrap_or(1i32);
-        Self {
-            product: String::from(p),
-            model: String::from(m),
-            version: v,
+    // Shows the current value of the item
+    // A device value the spec rejects is shown as close as the control allows
+    fn load(&mut self,item: &ConfItem,ui: &UI) {
+        let kept=item.kept().map( |raw| item.parse_unchecked(raw).unwrap_or_else( |_| Value::Text(raw.into()) ) );
+        match (&mut self.ctl,kept.as_ref().unwrap_or(item.value()),&item.kind) {
+            (Ctl::Check(c), Value::Check(v), _) => c.set_checked(ui,*v),
+            (Ctl::Spin(c), Value::Int(v), _) => c.set_value(ui,*v),
+            (Ctl::Combo(c), Value::Choice(v), ItemKind::Choice { values, .. }) => {
+                let idx=values.iter().position( |o| o.val == *v ).unwrap_or(0);
+                c.set_selected(ui,idx as i32);
+            },
+            (Ctl::Line(c), Value::Hex(v), _) |
+            (Ctl::Line(c), Value::Text(v), _) => c.set_value(ui,v),
+            _ => { }
         }
+        self.shown=match item.kept() {
+            Some(raw) => {
+                self.flag.set_text(ui,&trf("⚠ {} kept from the device",&[&raw]));
+                self.read(item,ui).ok()
+            },
+            None => {
+                self.flag.set_text(ui,"");
+                None
+            },
+        };
     }
-}
-impl PartialEq for Signature {
-    fn eq(&self, other: &Self) -> bool {
-        self.product == other.product && self.model==other.model && self.version==self.version
-    }
-}
 
+    // True while the control still shows a kept device value
+    fn unchanged(&self,item: &ConfItem,ui: &UI) -> bool {
+        self.shown.is_some() && self.read(item,ui).ok() == self.shown
+    }
 
-#[derive(Deserialize)]
-pub struct Section {
-    name: String,
-    help: String,
-    items: Vec<ConfItem>
-}
+    // Value entered by the user, checked against the item limits
+    fn value(&self,item: &ConfItem,ui: &UI) -> std::result::Result<Value,String> {
+        let v=self.read(item,ui)?;
+        item.check(&v)?;
+        Ok(v)
+    }
 
-impl Section {
-    pub fn find(&mut self, s:&str) -> Option<&mut ConfItem> {
-        let f=self.items.iter_mut().find( |e| e.is(s));
-        f
+    fn read(&self,item: &ConfItem,ui: &UI) -> std::result::Result<Value,String> {
+        let v=match (&self.ctl,&item.kind) {
+            (Ctl::Check(c), _) => Value::Check(c.checked(ui)),
+            (Ctl::Spin(c), _) => Value::Int(c.value(ui)),
+            (Ctl::Line(c), ItemKind::Hex { .. }) => Value::Hex(c.value(ui)),
+            (Ctl::Line(c), _) => Value::Text(c.value(ui)),
+            (Ctl::Combo(c), ItemKind::Choice { values, .. }) => {
+                let o=values.get(c.selected(ui) as usize).ok_or_else( || tr("nothing selected").to_string() )?;
+                Value::Choice(o.val)
+            },
+            _ => return Err(format!("no control for '{}'",item.id())),
+        };
+        Ok(v)
     }
 }
 
-#[derive(Deserialize)]
-pub struct Choice {
-    pub val: i32,
-    pub desc: String,
+// Differences between the snapshots selected in the history window
+fn show_diff(ui: &UI,from: &Combobox,to: &Combobox,out: &MultilineEntry,snaps: &[Snapshot]) {
+    let a=snaps.get(from.selected(ui) as usize);
+    let b=snaps.get(to.selected(ui) as usize);
+    let text=match (a,b) {
+        (Some(a),Some(b)) => {
+            let d=history::diff(&a.config,&b.config);
+            if d.is_empty() { tr("No differences").into() } else { d.join("\n") }
+        },
+        _ => String::new(),
+    };
+    out.clone().set_value(ui,&text);
 }
 
-#[derive(Deserialize)]
-pub enum ConfItem {
-    Int    { id: String, caption:String, val:i32,  vmax:i32, vmin:i32 ,
-            #[serde(skip)]
-             control: Option<Spinbox>,
-           },
-    Hex    { id: String, caption:String,val:String, maxlen:usize, lsb:bool,
-            #[serde(skip)]
-            control: Option<Entry>,
-           },
-    Text   { id: String, caption:String, val:String, maxlen:usize,
-            #[serde(skip)]
-            control: Option<Entry>,
-           },
-    Choice { id: String, caption:String ,val:usize,  values:Vec<Choice> ,
-            #[serde(skip)]
-            control: Option<Combobox>,
-           },
-    Check  { id: String, caption:String, val:bool,
-             #[serde(skip)]
-             control: Option<Checkbox>,
-           }
+// State of the connection of an editor
+#[derive(Clone,Copy,PartialEq)]
+enum Link {
+    Connected,
+    Disconnected,
+    // The port disappeared or stopped answering; reconnected automatically
+    // when the port scan sees it
+    Lost,
+    Reconnecting,
 }
 
-impl ConfItem {
-    pub fn is(&self,wid:&str) -> bool {
-        match self {
-            ConfItem::Check { id , .. } |
-            ConfItem::Int { id , .. } |
-            ConfItem::Hex { id, .. } |
-            ConfItem::Choice { id, .. } |
-            ConfItem::Text { id , .. } => id==wid
-        }
-    }
-
-    pub fn validate(&self,ui:&UI) -> (bool,&String) {
-        match self {
-            ConfItem::Check { caption, .. } => (true,caption),
-            ConfItem::Int { caption, .. } => (true,caption),
-            ConfItem::Choice { control:Some(c), caption , .. } => (c.selected(ui) >=0,caption) ,
-            ConfItem::Text { control:Some(c), maxlen: ml , caption, .. } => (c.value(ui).len() <= *ml , caption) ,
-            ConfItem::Hex { control: Some(c), maxlen: ml, caption, ..} => {
-                let v= c.value(ui);
-                //println!("v:{} , len:{}",v,v.len());
-                ( (v.len() <= (2*ml) && ConfItem::is_hex(&v)) , caption)
-            },
-            ConfItem::Text { control:None , caption, .. } |
-            ConfItem::Hex { control:None , caption, .. } |
-            ConfItem::Choice { control:None , caption, .. }  => (false,caption)
+// Editor window of one open device. Actions of its own controls go through
+// its own channel; `app` reaches the main loop (device list, closing).
+pub struct Editor {
+    id: usize,
+    port: String,
+    root: Root,
+    ui: UI,
+    win: Window,
+    serial: Option<Worker>,
+    mode: ConnectMode,
+    link: Link,
+    link_button: Button,
+    auto_reconnect: bool,
+    // Ports that never showed up in the port scan (e.g. typed by hand) are not watched
+    port_seen: bool,
+    // UTC time of the current connection
+    connected_at: Option<(String,Instant)>,
+    // Copy of the worker's command statistics, as of its last job
+    stats: CmdStats,
+    info: Label,
+    advice: Label,
+    cmd: Sender<Actions>,
+    inbox: Receiver<Actions>,
+    app: Sender<Actions>,
+    aspec: usize,
+    pending: Option<(Vec<(String,String)>,Report)>,
+    // Editor values when the firmware upload started, migrated once it is done
+    before_flash: Option<Vec<(String,String)>>,
+    // Device config read just before the last factory reset, and its history file
+    backup: Option<Vec<(String,String)>>,
+    backup_file: Option<PathBuf>,
+    // Config last read from or written to the device
+    device: Vec<(String,String)>,
+    // `ver` banner and `show` lines with the firmware's captions, as last read
+    device_info: Option<DeviceInfo>,
+    entries: Vec<ConfigEntry>,
+    // Spec drift found by the last read, shown in a banner while not empty
+    drift: Drift,
+    // What the firmware says of its limits (`spec`), asked once per connection
+    // when the device is in the spec
+    firmware_spec: Option<Device>,
+    spec_asked: bool,
+    drift_label: Label,
+    drift_box: Option<HorizontalBox>,
+    // Profile applied after the first read (--profile)
+    profile: Option<String>,
+    timing: Timeouts,
+    // Controls of the items of the device being edited, in spec order
+    widgets: Vec<Widget>,
+}
 
+impl Editor {
+    pub fn new(ui : UI,app :Sender<Actions>,id: usize,port: &str,mode: ConnectMode,root: Root,prefs: &Prefs) -> Self {
+        let (w,h)=prefs.editor_size;
+        let mut win= Window::new(&ui, &trf("Config editor - {}",&[&port]), w, h, WindowType::HasMenubar);
+        win.on_closing(&ui, {
+            let ui=ui.clone();
+            let c=app.clone();
+            move |w: &mut Window| {
+                w.hide(&ui);
+                let _=c.send(Actions::CloseEditor(id));
+            }
+        });
+        let info=Label::new(&ui,tr("Ready"));
+        let advice=Label::new(&ui,"");
+        let drift_label=Label::new(&ui,"");
+        let (cmd,inbox)=channel::<Actions>();
+        let mut link_button=Button::new(&ui,tr("Disconnect"));
+        link_button.on_clicked(&ui, {
+            let c=cmd.clone();
+            move |_| {
+                let _=c.send(Actions::ToggleLink);
+            }
+        });
+        Self {
+            id,
+            port: port.to_string(),
+            root,
+            ui: ui,
+            win: win,
+            serial: None,
+            mode,
+            link: Link::Disconnected,
+            link_button,
+            auto_reconnect: prefs.auto_reconnect,
+            port_seen: false,
+            connected_at: None,
+            stats: CmdStats::default(),
+            info: info,
+            advice: advice,
+            cmd: cmd,
+            inbox,
+            app,
+            aspec: 0,
+            pending: None,
+            before_flash: None,
+            backup: None,
+            backup_file: None,
+            device: vec!(),
+            device_info: None,
+            entries: vec!(),
+            drift: Drift::default(),
+            firmware_spec: None,
+            spec_asked: false,
+            drift_label,
+            drift_box: None,
+            profile: None,
+            timing: prefs.timeouts,
+            widgets: vec!(),
         }
     }
-    
-    fn  is_hex(s:&str) -> bool {
-        let re = Regex::new(r"[0123456789abcdefABCDEF]").unwrap();
-        //println!("slen {} count {}", s.len() , re.find_iter(s).count() );
-        s.len() >0 && s.len() % 2 == 0 && re.find_iter(s).count() == s.len()
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    pub fn port(&self) -> &str {
+        &self.port
+    }
+    pub fn set_device_info(&mut self,info: DeviceInfo) {
+        self.device_info=Some(info);
+    }
+    // Line shown in the device list
+    pub fn describe(&self) -> String {
+        let state=match self.link {
+            Link::Connected => "",
+            Link::Disconnected => tr(" (disconnected)"),
+            Link::Lost => tr(" (lost)"),
+            Link::Reconnecting => tr(" (reconnecting)"),
+        };
+        let name=self.device_info.as_ref().map( |i| format!("{} ",i.name) ).unwrap_or_default();
+        format!("{} - {}{}{}",self.port,name,self.root.spec[self.aspec].signature,state)
+    }
+    pub fn present(&mut self) {
+        self.win.show(&self.ui);
+    }
+    // Closes the window and the port; waits for the job in progress
+    pub fn close(&mut self) {
+        self.win.hide(&self.ui);
+        self.serial=None;
+    }
+    pub fn start_profile(&mut self,name: Option<String>) {
+        self.profile=name;
+    }
+    pub fn take_serial(&mut self,mut ser:BorosSerial) {
+        ser.set_timeouts(self.timing);
+        self.serial=Some(Worker::spawn(ser));
+        self.connected_at=Some((transcript::timestamp(),Instant::now()));
+        self.stats=CmdStats::default();
+        self.spec_asked=false;
+        self.set_link(Link::Connected);
     }
-    fn invert(s:&str) -> String {
-        let re = Regex::new(r"[0123456789abcdefABCDEF][0123456789abcdefABCDEF]").unwrap();
-        let mut x :Vec<&str>=re.find_iter(s).map(|x| { x.as_str() }).collect();
-        x.reverse();
-        x.join("")
+    fn set_link(&mut self,link: Link) {
+        self.link=link;
+        let text=if link == Link::Connected { tr("Disconnect") } else { tr("Connect") };
+        self.link_button.set_text(&self.ui,text);
+    }
+    // Port and connect mode used to reconnect
+    pub fn connection(&self) -> (String,ConnectMode) {
+        (self.port.clone(),self.mode)
+    }
+
+    // Closes the port but keeps the editor and its values
+    pub fn disconnect(&mut self) {
+        self.serial=None;
+        self.set_link(Link::Disconnected);
+        self.editor_info(tr("Disconnected, press Connect to talk to the device again"));
     }
 
-    pub fn from_device(&mut self,ui:&UI,v:&str) {
-        match self {
-            ConfItem::Check { control: Some(c), .. } => c.set_checked(ui,v == "1" ) ,
-            ConfItem::Int   { control: Some(c), val:def, .. } => {
-                let v=v.parse::<i32>().unwrap_or(*def);
-                c.set_value(ui,v);
+    // Follows the presence of the port; true when the state shown in the device list changed
+    pub fn check_link(&mut self,present: bool) -> bool {
+        if present {
+            self.port_seen=true;
+        } else if !self.port_seen {
+            return false;
+        }
+        match self.link {
+            Link::Connected if !present => {
+                self.serial=None;
+                self.set_link(Link::Lost);
+                self.editor_info(tr("¡¡¡ Link lost, the port is gone. Waiting for the device to return..."));
+                true
+            },
+            Link::Lost if present && self.auto_reconnect => {
+                self.set_link(Link::Reconnecting);
+                self.editor_info(tr("Port is back, reconnecting..."));
+                let _=self.app.send(Actions::Reconnect(self.id));
+                true
+            },
+            _ => false,
+        }
+    }
+
+    // A worker job found the device gone (write error or no answer). The
+    // port is closed; the port scan or Connect brings it back.
+    fn link_lost(&mut self,e: &str) {
+        if self.link == Link::Connected {
+            self.serial=None;
+            self.set_link(Link::Lost);
+            self.editor_info(&trf("¡¡¡ Link lost ({}). Waiting for the device to return...",&[&e]));
+        }
+    }
+
+    // Result of a reconnection; the device must report the signature being edited
+    pub fn reconnected(&mut self,res: std::result::Result<Opened,String>) {
+        let auto=self.link == Link::Reconnecting;
+        let sig=self.root.spec[self.aspec].signature.clone();
+        match res {
+            Ok(o) if o.info.signature == sig => {
+                self.take_serial(*o.ser);
+                self.device_info=Some(o.info);
+                self.editor_info(&trf("Reconnected to {}, editor values kept",&[&sig]));
+            },
+            Ok(o) => {
+                self.set_link(Link::Disconnected);
+                let msg=trf("The device on {} now reports {} instead of {}.\nClose this editor and open the port again to edit it.",
+                              &[&self.port,&o.info.signature,&sig]);
+                self.win.modal_err(&self.ui,tr("Reconnect"),&msg);
+                self.editor_info(tr("Not reconnected, a different device answered"));
             },
-            ConfItem::Choice { control: Some(c), values:options, val:def, .. } => {
-                let vp=v.parse::<i32>().unwrap_or(0);
-                let idx=options.iter().position( |o| o.val==vp ).unwrap_or(*def);
-                c.set_selected(ui,idx as i32); 
+            Err(msg) => {
+                self.set_link(Link::Disconnected);
+                if !auto {
+                    self.win.modal_err(&self.ui,tr("Reconnect"),&msg);
+                }
+                self.editor_info(&trf("Reconnection failed: {}. Press Connect to retry",&[&msg]));
             },
-            ConfItem::Text { control: Some(c), .. } => c.set_value(ui,v),
-            ConfItem::Hex  { control: Some(c), lsb: l, ..} => {
-                if *l { 
-                    c.set_value(ui,ConfItem::invert(v).as_str()) 
-                } else { 
-                    c.set_value(ui,v)
+        }
+    }
+
+    // False (and a hint in the status line) when no device is attached
+    fn connected(&mut self) -> bool {
+        if self.serial.is_none() {
+            self.editor_info(tr("Not connected, press Connect first"));
+        }
+        self.serial.is_some()
+    }
+
+    pub fn set_prefs(&mut self,prefs: &Prefs) {
+        self.auto_reconnect=prefs.auto_reconnect;
+        self.timing=prefs.timeouts;
+        let t=self.timing;
+        if let Some(w) = &self.serial {
+            w.post(move |ser| ser.set_timeouts(t));
+        }
+    }
+    pub fn set_transcript(&mut self,t: Option<Transcript>) {
+        if let Some(w) = &self.serial {
+            w.post(move |ser| ser.set_transcript(t));
+        }
+    }
+    // Queues a job on the worker of the device. A link failure found while it
+    // runs comes back as LinkLost, after what the job reports itself.
+    fn post<F>(&self,job: F)
+        where F: FnOnce(&mut BorosSerial) + Send + 'static {
+        if let Some(w) = &self.serial {
+            let c=self.cmd.clone();
+            w.post(move |ser| {
+                job(ser);
+                let _=c.send(Actions::Stats(ser.stats()));
+                if let Some(e)=ser.take_link_error() {
+                    let _=c.send(Actions::LinkLost(e));
                 }
+            });
+        }
+    }
+
+    // Handles one pending action of this editor per tick, so status texts
+    // are drawn before the next action runs. True when the link state changed.
+    pub fn tick(&mut self) -> bool {
+        let link=self.link;
+        if let Ok(msg)=self.inbox.try_recv() {
+            match msg {
+                Actions::EditorInfo(s) => self.editor_info(&s),
+                Actions::LinkLost(e) => self.link_lost(&e),
+                Actions::Stats(st) => self.stats=st,
+                Actions::ToggleLink => {
+                    if self.link == Link::Connected {
+                        self.disconnect();
+                    } else if self.link != Link::Reconnecting {
+                        self.set_link(Link::Reconnecting);
+                        self.editor_info(tr("Connecting..."));
+                        let _=self.app.send(Actions::Reconnect(self.id));
+                    }
+                },
+                Actions::Reset  => self.reset(),
+                Actions::BackupRead(r) => self.backup_read(r),
+                Actions::FactoryDone(ok) => self.factory_done(ok),
+                Actions::RestoreBackup => self.restore_backup(),
+                Actions::ShowHistory => self.show_history(),
+                Actions::ShowProfiles => self.show_profiles(),
+                Actions::ApplyProfile(n) => self.apply_profile(&n),
+                Actions::SaveProfile(n) => self.save_profile(&n),
+                Actions::DeleteProfile(n) => self.delete_profile(&n),
+                Actions::Compare => self.compare(),
+                Actions::CompareRead(r) => self.compare_read(r),
+                Actions::ApplyValues(v) => self.apply_picked(v),
+                Actions::RestoreSnapshot(p) => self.restore_snapshot(&p),
+                Actions::DefaultItem(id) => self.default_item(&id),
+                Actions::DefaultSection(n) => self.default_section(n),
+                Actions::DefaultAll => self.default_all(),
+                Actions::ReadConfig => self.read_config(),
+                Actions::FirmwareSpec(d) => self.firmware_spec=d,
+                Actions::ConfigRead(r) => self.config_read(r),
+                Actions::SaveConfig => self.save_config(),
+                Actions::ConfigWritten(r) => self.config_written(r),
+                Actions::ShowDrift => self.show_drift(),
+                Actions::ShowDeviceInfo => self.show_device_info(),
+                Actions::Flash(p) => self.flash(&p),
+                Actions::Flashed(r) => self.flashed(r),
+                Actions::SaveFile(p) => self.save_file(&p),
+                Actions::LoadFile(p) => self.load_file(&p),
+                _ => {}
             }
-            _ => { }
-        } // Match
+        }
+        self.link != link
+    }
+
+    pub fn check_signature(&self,sig: &Signature ) -> usize {
+        self.root.find(sig).unwrap_or(usize::MAX)
+    }
+
+    // Reads the device config on the worker as a backup; backup_read runs `fac`
+    // once it is stored. No reset is done if the backup can't be read.
+    pub fn reset(&mut self) {
+        if !self.connected() {
+            return;
+        }
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            let _=c.send(Actions::BackupRead(ser.get_config().map_err( |e| e.to_string() )));
+        });
     }
 
-    pub fn to_device(&self,ui:&UI) -> String {
-        match self {
-            ConfItem::Check { control:Some(c), id, .. } => { 
-                let v= if c.checked(ui) { "1" } else { "0" };
-                format!("{} {}",id,v)
+    fn backup_read(&mut self,res: std::result::Result<Vec<(String,String)>,String>) {
+        let config=match res {
+            Ok(c) if !c.is_empty() => c,
+            Ok(_) => {
+                self.win.modal_err(&self.ui,tr("Factory reset"),tr("The device reported no configuration to back up, factory reset cancelled"));
+                self.editor_info(tr("Factory settings not done"));
+                return;
             },
-            ConfItem::Int { control:Some(c), id,  .. } => format!("{} {}",id,c.value(ui)),
-            ConfItem::Hex { control:Some(c), lsb: l, id,  .. } => {
-                let mut v=c.value(ui);
-                if *l { v= ConfItem::invert(&v) }
-                format!("{} {}",id,v)
+            Err(e) => {
+                let msg=trf("The configuration could not be backed up ({}), factory reset cancelled",&[&e]);
+                self.win.modal_err(&self.ui,tr("Factory reset"),&msg);
+                self.editor_info(tr("Factory settings not done"));
+                return;
             },
-            ConfItem::Text   { control:Some(c), id,  .. } => format!("{} {}",id,c.value(ui)),
-            ConfItem::Choice { control:Some(c), values:v,  id, .. } => {
-                let idx = c.selected(ui);
-                format!("{} {}",id,v[idx as usize].val)
+        };
+        // Kept with the history of this device (named after its id and the time)
+        let sig=self.root.spec[self.aspec].signature.clone();
+        self.backup_file=History::open().record(&sig,&config,"backup").ok();
+        self.backup=Some(config);
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            let ok=matches!(ser.do_cmd("fac"),Ok((true,_)));
+            let _=c.send(Actions::FactoryDone(ok));
+        });
+    }
+
+    fn factory_done(&mut self,ok: bool) {
+        if !ok {
+            self.editor_info(tr("Factory settings failed."));
+            return;
+        }
+        match &self.backup_file {
+            Some(path) => {
+                let msg=trf("Factory settings done, backup in {}",&[&path.display()]);
+                self.editor_info(&msg);
             },
-            _ => "".into()  
+            None => self.editor_info(tr("Factory settings done, backup kept in memory only")),
         }
+        self.read_config();
     }
 
-    pub fn build_control(&mut self,ui:&UI) -> HorizontalBox {
-        let mut hb=HorizontalBox::new(ui);
-        let (caption,control) : (&str,Control) = match self {
-            ConfItem::Text { control: c , val: v, caption: cap, ..} => {
-                 let mut con = Entry::new(ui);
-                 con.set_value(ui,v);
-                 let rcon=con.clone();
-                 *c=Some(con);
-                 (cap, rcon.into())
+    // Writes the config saved by the last factory reset back to the device
+    pub fn restore_backup(&mut self) {
+        match self.backup.clone() {
+            Some(values) => {
+                let mut report=Report::default();
+                self.apply_values(values,&mut report);
+                self.save_config();
             },
-            ConfItem::Int { control: c , vmax:vma, vmin: vmi ,caption:cap, val:v, ..} => {
-                let mut con= Spinbox::new(ui,*vmi,*vma);
-                con.set_value(ui,*v);
-                let rcon=con.clone();
-                *c=Some(con);
-                (cap,rcon.into())
+            None => self.win.modal_msg(&self.ui,tr("Restore"),tr("There is no configuration backed up by a factory reset in this session.")),
+        }
+    }
+
+    pub fn editor_info(&mut self,s: &str) {
+        self.info.set_text(&self.ui,s);
+    }
+    // Copies the controls into the values of the items. Every field is checked
+    // first: on error no item is changed. Device values kept because the spec
+    // rejects them stay as they are while their control is not changed.
+    fn store_widgets(&mut self) -> std::result::Result<(),String> {
+        let ui=&self.ui;
+        let mut values=vec!();
+        let mut widgets=self.widgets.iter();
+        for sec in &self.root.spec[self.aspec].sections {
+            for (item,w) in sec.items.iter().zip(&mut widgets) {
+                if w.unchanged(item,ui) {
+                    values.push(None);
+                    continue;
+                }
+                let v=w.value(item,ui).map_err( |e| trf("The field '{}' in tab '{}' is not valid: {}.\nCheck format and length.",&[&item.caption(),&sec.name,&e]) )?;
+                values.push(Some(v));
+            }
+        }
+        for (item,v) in self.root.spec[self.aspec].items_mut().zip(values) {
+            if let Some(v)=v {
+                item.set(v)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Shows the values of the items in the controls
+    fn load_widgets(&mut self) {
+        let ui=&self.ui;
+        for (item,w) in self.root.spec[self.aspec].items().zip(self.widgets.iter_mut()) {
+            w.load(item,ui);
+        }
+    }
+
+    // Sets item `id` from a raw value and shows it; values the spec does not
+    // accept are kept as they are and flagged. False if the spec has no such item.
+    fn set_field(&mut self,id: &str,raw: &str) -> bool {
+        let dev=&mut self.root.spec[self.aspec];
+        match dev.items_mut().zip(self.widgets.iter_mut()).find( |(i,_)| i.is(id) ) {
+            Some((item,w)) => {
+                let _=item.from_device(raw);
+                w.load(item,&self.ui);
+                true
             },
-            ConfItem::Check { control: c , val: v, caption: cap, ..} => {
-                let mut con = Checkbox::new(ui,cap);
-                con.set_checked(ui,*v);
-                let rcon=con.clone();
-                *c=Some(con);
-                ("",rcon.into())
-           },
-           ConfItem::Hex { control: c , val: v, caption: cap, ..} => {
-                let mut con = Entry::new(ui);
-                con.set_value(ui,v);
-                let rcon=con.clone();
-                *c=Some(con);
-                (cap, rcon.into())
-           },
-           ConfItem::Choice {control:c, val: idx, caption:cap, values: vals,.. } => {
-               let mut con = Combobox::new(ui);
-               for v in vals {
-                   con.append(ui,v.desc.as_str());
-               }
-               con.set_selected(ui,*idx as i32);
-               let rcon=con.clone();
-               *c=Some(con);
-               (cap,rcon.into())
-           },
-            //_ => ( "",HorizontalSeparator::new(ui).into() )
-        };
+            None => false,
+        }
+    }
 
-        if caption != ""  {
-            hb.append(ui,Label::new(ui,caption),LayoutStrategy::Compact);
+    pub fn default_item(&mut self,id: &str) {
+        let dev=&mut self.root.spec[self.aspec];
+        if let Some((item,w))=dev.items_mut().zip(self.widgets.iter_mut()).find( |(i,_)| i.is(id) ) {
+            item.reset();
+            w.load(item,&self.ui);
         }
-        hb.append(ui,control,LayoutStrategy::Stretchy);
-        hb.set_padded(ui,true);
-        hb
+        self.editor_info(&trf("Default value restored for '{}', not yet written",&[&id]));
     }
 
-}    
+    pub fn default_section(&mut self,n: usize) {
+        let ui=&self.ui;
+        let dev=&mut self.root.spec[self.aspec];
+        let first: usize=dev.sections.iter().take(n).map( |s| s.items.len() ).sum();
+        if let Some(sec)=dev.sections.get_mut(n) {
+            for (item,w) in sec.items.iter_mut().zip(self.widgets.iter_mut().skip(first)) {
+                item.reset();
+                w.load(item,ui);
+            }
+            let msg=trf("Defaults restored in '{}', not yet written",&[&sec.name]);
+            self.info.set_text(ui,&msg);
+        }
+    }
 
-pub struct Editor {
-    root: Root,
-    ui: UI,
-    win: Window,
-    serial: Option<Box<BorosSerial>>,
-    info: Label,
-    cmd: Sender<Actions>,
-    aspec: usize,
+    pub fn default_all(&mut self) {
+        self.root.spec[self.aspec].reset();
+        self.load_widgets();
+        self.editor_info(tr("All defaults restored, not yet written"));
+    }
 
-}
+    // Raw (id,value) pairs currently shown in the editor, or the message of
+    // the first invalid field
+    pub fn current_values(&mut self) -> std::result::Result<Vec<(String,String)>,String> {
+        self.store_widgets()?;
+        Ok(self.root.spec[self.aspec].values())
+    }
 
-impl Editor {
-    pub fn new(ui : UI,cmd :Sender<Actions>) -> Result<Self> {
-        #[cfg(debug_assertions)] let str=std::fs::read_to_string("./spec.yml").unwrap();
-        #[cfg(not(debug_assertions))] let str=String::from(yml);
-        let r= from_str(str.as_str())?;
-        let win= Window::new(&ui, "Config editor", 640, 380, WindowType::NoMenubar);
-        let info=Label::new(&ui,"Ready");
-        Ok(Self {
-            root: r,
-            ui: ui,
-            win: win,
-            serial: None,
-            info: info,
-            cmd: cmd,
-            aspec: 0,
-        })
+    // Reports fields that can't be stored
+    fn invalid_fields(&mut self,title: &str,msg: &str) {
+        self.win.modal_err(&self.ui,title,msg);
+        self.editor_info(tr("¡¡¡ Invalid fields"));
     }
-    pub fn take_serial(&mut self,ser:Box<BorosSerial>) {
-        self.serial=Some(ser);
+
+    // Loads raw values into the controls; ids missing from the spec are reported as dropped
+    fn apply_values(&mut self,values: Vec<(String,String)>,report: &mut Report) {
+        for (id,val) in values {
+            if !self.set_field(&id,&val) {
+                report.dropped.push(trf("{} (not in spec)",&[&id]));
+            }
+        }
+    }
+
+    pub fn save_file(&mut self,path: &Path) {
+        let values=match self.current_values() {
+            Ok(v) => v,
+            Err(msg) => return self.invalid_fields(tr("Save"),&msg),
+        };
+        let f=ConfigFile::new(self.root.spec[self.aspec].signature.clone(),values);
+        match f.save(path) {
+            Ok(_) => self.editor_info(&trf("Config saved to {}",&[&path.display()])),
+            Err(e) => self.win.modal_err(&self.ui,tr("Save"),&trf("Can't write {}: {}",&[&path.display(),&e])),
+        }
+    }
+
+    // Loads a config file, migrating it when it was saved from an older firmware
+    pub fn load_file(&mut self,path: &Path) {
+        let f=match ConfigFile::load(path) {
+            Ok(f) => f,
+            Err(e) => {
+                self.win.modal_err(&self.ui,tr("Load"),&trf("Can't read {}: {}",&[&path.display(),&e]));
+                return;
+            }
+        };
+        if self.apply_config(&f,&path.display().to_string()) {
+            self.editor_info(tr("Config loaded from file, not yet written to the device"));
+        }
+    }
+
+    // Editor values to copy to another device; the device id is left out
+    pub fn shared_config(&mut self) -> std::result::Result<ConfigFile,String> {
+        let values=self.current_values()?.into_iter().filter( |(id,_)| id != "id" ).collect();
+        Ok(ConfigFile::new(self.root.spec[self.aspec].signature.clone(),values))
+    }
+
+    pub fn copy_from(&mut self,f: &ConfigFile,origin: &str) {
+        if self.apply_config(f,origin) {
+            self.editor_info(&trf("Config copied from {}, not yet written to the device",&[&origin]));
+            self.present();
+        }
     }
-    pub fn get_and_check_signature(&mut self) -> usize {
-        if let Some(ser) = &mut self.serial {
-            if ser.connect() {
-                if let Ok(sig)=ser.get_signature() {
-                    return self.check_signature(&sig);
+
+    // Puts a stored config into the editor, migrating it from older firmware versions
+    fn apply_config(&mut self,f: &ConfigFile,origin: &str) -> bool {
+        let sig=self.root.spec[self.aspec].signature.clone();
+        if !f.signature.same_model(&sig) || f.signature.version > sig.version {
+            self.win.modal_err(&self.ui,tr("Load"),&trf("{} was saved from a {} device and can't be applied to {}",&[&origin,&f.signature,&sig]));
+            return false;
+        }
+        let mut report=Report::default();
+        let values=migrate::apply(&self.root.upgrade_rules(&f.signature,&sig),f.values(),&mut report);
+        self.apply_values(values,&mut report);
+        if f.signature != sig || !report.is_empty() {
+            let msg=trf("{} was saved from {} and converted for {}.\n{}",&[&origin,&f.signature,&sig,&reports::migration(&report)]);
+            self.win.modal_msg(&self.ui,tr("Configuration migrated"),&msg);
+        }
+        true
+    }
+
+    fn record(&mut self,values: Vec<(String,String)>,kind: &str) {
+        let sig=&self.root.spec[self.aspec].signature;
+        if let Err(e)=History::open().record(sig,&values,kind) {
+            eprintln!("Can't store the configuration history: {}",e);
+        }
+        self.device=values;
+    }
+
+    // Writes a snapshot from the history back to the device
+    pub fn restore_snapshot(&mut self,path: &Path) {
+        match History::load(path) {
+            Ok(snap) => {
+                if self.apply_config(&snap.config,&trf("The snapshot of {}",&[&snap.time])) {
+                    self.save_config();
                 }
-            } 
+            },
+            Err(e) => self.win.modal_err(&self.ui,tr("History"),&trf("Can't read {}: {}",&[&path.display(),&e])),
         }
-        usize::MAX
     }
-    pub fn check_signature(&self,sig: &Signature ) -> usize {
-        for (i,e) in self.root.spec.iter().enumerate() {
-            if e.signature == *sig {
-                return i;
+
+    // Side by side view of device, editor and spec default values, section by section.
+    // For each field the user picks the side copied into the editor.
+    // The device is read on the worker, the window opens in compare_read.
+    pub fn compare(&mut self) {
+        if !self.connected() {
+            return;
+        }
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            let _=c.send(Actions::CompareRead(ser.get_config().map_err( |e| e.to_string() )));
+        });
+    }
+
+    fn compare_read(&mut self,res: std::result::Result<Vec<(String,String)>,String>) {
+        let device=match res {
+            Ok(c) => c,
+            Err(e) => {
+                self.win.modal_err(&self.ui,tr("Compare"),&trf("Can't read the device configuration: {}",&[&e]));
+                self.editor_info(tr("Ready"));
+                return;
+            },
+        };
+        let editor=match self.current_values() {
+            Ok(v) => v,
+            Err(msg) => return self.invalid_fields(tr("Compare"),&msg),
+        };
+        let ui=&self.ui;
+        let dev=&self.root.spec[self.aspec];
+        let mut win=Window::new(ui,&trf("Compare {}",&[&dev.signature]),640,420,WindowType::NoMenubar);
+        let mut tabs=TabGroup::new(ui);
+        let mut picks: Vec<(String,Combobox,[Option<String>;3])>=vec!();
+        let mut differ=0;
+
+        for sec in &dev.sections {
+            let mut tab=VerticalBox::new(ui);
+            tab.set_padded(ui,true);
+            let mut head=HorizontalBox::new(ui);
+            head.set_padded(ui,true);
+            for h in &["Field","Device","Editor","Default","Use"] {
+                head.append(ui,Label::new(ui,tr(h)),LayoutStrategy::Stretchy);
             }
+            tab.append(ui,head,LayoutStrategy::Compact);
+            tab.append(ui,HorizontalSeparator::new(ui),LayoutStrategy::Compact);
+            for item in &sec.items {
+                let sides=[
+                    device.iter().find( |v| v.0 == item.id() ).map( |v| v.1.clone() ),
+                    editor.iter().find( |v| v.0 == item.id() ).map( |v| v.1.clone() ),
+                    item.default_raw(),
+                ];
+                let same=sides[0] == sides[1] && (sides[2].is_none() || sides[1] == sides[2]);
+                if sides[0] != sides[1] { differ+=1 }
+                let mark=if same { "" } else if sides[0] != sides[1] { "≠ " } else { "* " };
+                let mut row=HorizontalBox::new(ui);
+                row.set_padded(ui,true);
+                row.append(ui,Label::new(ui,&format!("{}{}",mark,item.caption())),LayoutStrategy::Stretchy);
+                for v in &sides {
+                    let text=v.as_ref().map( |r| item.describe(r) ).unwrap_or_else( || "-".into() );
+                    row.append(ui,Label::new(ui,&text),LayoutStrategy::Stretchy);
+                }
+                let mut pick=Combobox::new(ui);
+                for o in &["Device","Editor","Default"] {
+                    pick.append(ui,tr(o));
+                }
+                pick.set_selected(ui,1);
+                row.append(ui,pick.clone(),LayoutStrategy::Stretchy);
+                tab.append(ui,row,LayoutStrategy::Compact);
+                picks.push((item.id().to_string(),pick,sides));
+            }
+            let n=tabs.append(ui,sec.name.get(),tab);
+            tabs.set_margined(ui,n-1,true);
+        }
+
+        let mut apply=Button::new(ui,tr("Apply to editor"));
+        let mut close=Button::new(ui,tr("Close"));
+        apply.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let mut w=win.clone();
+            move |_| {
+                let values: Vec<(String,String)>=picks.iter()
+                    .filter_map( |(id,pick,sides)| {
+                        let n=pick.selected(&ui);
+                        if n == 1 { return None }
+                        sides.get(n as usize).cloned().flatten().map( |v| (id.clone(),v) )
+                    }).collect();
+                let _=c.send(Actions::ApplyValues(values));
+                w.hide(&ui);
+            }
+        });
+        close.on_clicked(ui, {
+            let ui=ui.clone();
+            let mut w=win.clone();
+            move |_| w.hide(&ui)
+        });
+
+        let mut vbox=VerticalBox::new(ui);
+        vbox.set_padded(ui,true);
+        let summary=trf("{} field(s) differ between the device and the editor (≠). '*' marks values that differ from the spec default.",&[&differ]);
+        vbox.append(ui,Label::new(ui,&summary),LayoutStrategy::Compact);
+        vbox.append(ui,tabs,LayoutStrategy::Stretchy);
+        let mut bbox=HorizontalBox::new(ui);
+        bbox.set_padded(ui,true);
+        bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+        bbox.append(ui,close,LayoutStrategy::Compact);
+        bbox.append(ui,apply,LayoutStrategy::Compact);
+        vbox.append(ui,bbox,LayoutStrategy::Compact);
+        win.on_closing(ui, {
+            let ui=ui.clone();
+            move |w: &mut Window| w.hide(&ui)
+        });
+        win.set_margined(ui,true);
+        win.set_child(ui,vbox);
+        win.show(ui);
+        self.editor_info(tr("Ready"));
+    }
+
+    // Values picked in the compare window
+    pub fn apply_picked(&mut self,values: Vec<(String,String)>) {
+        let n=values.len();
+        let mut report=Report::default();
+        self.apply_values(values,&mut report);
+        self.editor_info(&trf("{} field(s) updated from the comparison, not yet written",&[&n]));
+    }
+
+    pub fn apply_profile(&mut self,name: &str) {
+        match Profiles::open().load(name) {
+            Ok(p) => {
+                if self.apply_config(&p.config,&trf("Profile '{}'",&[&name])) {
+                    self.editor_info(&trf("Profile '{}' applied ({} fields), not yet written",&[&name,&p.config.values.len()]));
+                }
+            },
+            Err(e) => self.win.modal_err(&self.ui,tr("Profiles"),&trf("Can't load profile '{}': {}",&[&name,&e])),
         }
-        usize::MAX
     }
 
-    pub fn reset(&mut self) {
-        if let Some(ser) = &mut self.serial {
-            let (ok,_) = ser.do_cmd("fac").unwrap_or((false,vec!()));
-            if ok {
-                self.editor_info("Factory settings done");
-            } else {
-                self.editor_info("Factory settings failed.")
+    // Stores the fields that differ from the spec defaults, except the device
+    // id. Fields without a default (generic editor) are always stored.
+    pub fn save_profile(&mut self,name: &str) {
+        let current=match self.current_values() {
+            Ok(v) => v,
+            Err(msg) => return self.invalid_fields(tr("Profiles"),&msg),
+        };
+        let ui=&self.ui;
+        let dev=&self.root.spec[self.aspec];
+        let values: Vec<(String,String)>=current.into_iter()
+            .filter( |(id,v)| id != "id" && matches!(dev.find(id), Some(i) if i.default_raw().as_ref() != Some(v)) )
+            .collect();
+        let p=Profile { name: name.into(), config: ConfigFile::new(dev.signature.clone(),values) };
+        match Profiles::open().save(&p) {
+            Ok(_) => self.editor_info(&trf("Profile '{}' saved with {} fields",&[&name,&p.config.values.len()])),
+            Err(e) => self.win.modal_err(ui,tr("Profiles"),&trf("Can't save profile '{}': {}",&[&name,&e])),
+        }
+    }
+
+    pub fn delete_profile(&mut self,name: &str) {
+        match Profiles::open().remove(name) {
+            Ok(_) => self.editor_info(&trf("Profile '{}' deleted",&[&name])),
+            Err(e) => self.win.modal_err(&self.ui,tr("Profiles"),&trf("Can't delete profile '{}': {}",&[&name,&e])),
+        }
+    }
+
+    pub fn show_profiles(&mut self) {
+        let ui=&self.ui;
+        let dev=&self.root.spec[self.aspec];
+        let list=Rc::new(Profiles::open().for_device(&dev.signature));
+        let mut win=Window::new(ui,&trf("Profiles for {}",&[&dev.signature]),420,320,WindowType::NoMenubar);
+
+        let mut combo=Combobox::new(ui);
+        for p in list.iter() {
+            combo.append(ui,&p.name);
+        }
+        let content=MultilineEntry::new(ui);
+        if !list.is_empty() {
+            combo.set_selected(ui,0);
+            content.clone().set_value(ui,&list[0].to_string());
+        }
+        combo.on_selected(ui, {
+            let ui=ui.clone();
+            let list=list.clone();
+            let mut content=content.clone();
+            move |n| {
+                let text=list.get(n as usize).map( |p| p.to_string() ).unwrap_or_default();
+                content.set_value(&ui,&text);
             }
+        });
+
+        let name=Entry::new(ui);
+        let mut apply=Button::new(ui,tr("Apply"));
+        let mut delete=Button::new(ui,tr("Delete"));
+        let mut save=Button::new(ui,tr("Save current as"));
+        let mut close=Button::new(ui,tr("Close"));
+        let selected={
+            let ui=ui.clone();
+            let combo=combo.clone();
+            let list=list.clone();
+            move || list.get(combo.selected(&ui) as usize).map( |p| p.name.clone() )
+        };
+        apply.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let mut w=win.clone();
+            let sel=selected.clone();
+            move |_| {
+                if let Some(n)=sel() {
+                    let _=c.send(Actions::ApplyProfile(n));
+                    w.hide(&ui);
+                }
+            }
+        });
+        delete.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let mut w=win.clone();
+            let sel=selected.clone();
+            move |_| {
+                if let Some(n)=sel() {
+                    let c=c.clone();
+                    dialogs::confirm(&ui,tr("Profiles"),&trf("Delete the profile '{}'?",&[&n]), move || {
+                        let _=c.send(Actions::DeleteProfile(n.clone()));
+                    });
+                    w.hide(&ui);
+                }
+            }
+        });
+        save.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let mut w=win.clone();
+            let name=name.clone();
+            move |_| {
+                let n=name.value(&ui).trim().to_string();
+                if n.is_empty() {
+                    w.modal_err(&ui,tr("Profiles"),tr("Type a name for the new profile"));
+                } else {
+                    let _=c.send(Actions::SaveProfile(n));
+                    w.hide(&ui);
+                }
+            }
+        });
+        close.on_clicked(ui, {
+            let ui=ui.clone();
+            let mut w=win.clone();
+            move |_| w.hide(&ui)
+        });
+
+        let mut vbox=VerticalBox::new(ui);
+        vbox.set_padded(ui,true);
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,combo,LayoutStrategy::Stretchy);
+        hb.append(ui,apply,LayoutStrategy::Compact);
+        hb.append(ui,delete,LayoutStrategy::Compact);
+        vbox.append(ui,hb,LayoutStrategy::Compact);
+        vbox.append(ui,content,LayoutStrategy::Stretchy);
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,name,LayoutStrategy::Stretchy);
+        hb.append(ui,save,LayoutStrategy::Compact);
+        vbox.append(ui,hb,LayoutStrategy::Compact);
+        vbox.append(ui,Label::new(ui,tr("New profiles keep the fields that differ from the defaults, except the Device ID.")),LayoutStrategy::Compact);
+        let mut bbox=HorizontalBox::new(ui);
+        bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+        bbox.append(ui,close,LayoutStrategy::Compact);
+        vbox.append(ui,bbox,LayoutStrategy::Compact);
+        win.on_closing(ui, {
+            let ui=ui.clone();
+            move |w: &mut Window| w.hide(&ui)
+        });
+        win.set_margined(ui,true);
+        win.set_child(ui,vbox);
+        win.show(ui);
+    }
+
+    pub fn show_history(&mut self) {
+        let values=if self.device.is_empty() {
+            match self.current_values() {
+                Ok(v) => v,
+                Err(msg) => return self.invalid_fields(tr("History"),&msg),
+            }
+        } else {
+            self.device.clone()
+        };
+        let ui=&self.ui;
+        let sig=&self.root.spec[self.aspec].signature;
+        let entries=History::open().list(sig,&values);
+        if entries.is_empty() {
+            self.win.modal_msg(ui,tr("History"),tr("No configuration of this device has been stored yet."));
+            return;
         }
+        let snaps: Rc<Vec<Snapshot>>=Rc::new(entries.iter().filter_map( |e| History::load(&e.path).ok() ).collect());
+        let paths: Vec<_>=entries.iter().map( |e| e.path.clone() ).collect();
+
+        let mut win=Window::new(ui,&trf("History of {}",&[&sig]),520,400,WindowType::NoMenubar);
+        let mut from=Combobox::new(ui);
+        let mut to=Combobox::new(ui);
+        for e in &entries {
+            from.append(ui,&e.to_string());
+            to.append(ui,&e.to_string());
+        }
+        let last=entries.len() as i32 - 1;
+        from.set_selected(ui,(last-1).max(0));
+        to.set_selected(ui,last);
+        let changes=MultilineEntry::new(ui);
+        show_diff(ui,&from,&to,&changes,&snaps);
+        from.on_selected(ui, {
+            let ui=ui.clone();
+            let (f,t,c,s)=(from.clone(),to.clone(),changes.clone(),snaps.clone());
+            move |_| show_diff(&ui,&f,&t,&c,&s)
+        });
+        to.on_selected(ui, {
+            let ui=ui.clone();
+            let (f,t,c,s)=(from.clone(),to.clone(),changes.clone(),snaps.clone());
+            move |_| show_diff(&ui,&f,&t,&c,&s)
+        });
+
+        let mut restore=Button::new(ui,tr("Restore 'To' snapshot"));
+        let mut close=Button::new(ui,tr("Close"));
+        restore.on_clicked(ui, {
+            let ui=ui.clone();
+            let c=self.cmd.clone();
+            let to=to.clone();
+            let mut w=win.clone();
+            move |_| {
+                if let Some(p)=paths.get(to.selected(&ui) as usize) {
+                    let _=c.send(Actions::EditorInfo(tr("Restoring snapshot...").into()));
+                    let _=c.send(Actions::RestoreSnapshot(p.clone()));
+                    w.hide(&ui);
+                }
+            }
+        });
+        close.on_clicked(ui, {
+            let ui=ui.clone();
+            let mut w=win.clone();
+            move |_| w.hide(&ui)
+        });
+
+        let mut vbox=VerticalBox::new(ui);
+        vbox.set_padded(ui,true);
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,Label::new(ui,tr("From")),LayoutStrategy::Compact);
+        hb.append(ui,from,LayoutStrategy::Stretchy);
+        hb.append(ui,Label::new(ui,tr("To")),LayoutStrategy::Compact);
+        hb.append(ui,to,LayoutStrategy::Stretchy);
+        vbox.append(ui,hb,LayoutStrategy::Compact);
+        vbox.append(ui,changes,LayoutStrategy::Stretchy);
+        let mut bbox=HorizontalBox::new(ui);
+        bbox.set_padded(ui,true);
+        bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+        bbox.append(ui,close,LayoutStrategy::Compact);
+        bbox.append(ui,restore,LayoutStrategy::Compact);
+        vbox.append(ui,bbox,LayoutStrategy::Compact);
+        win.on_closing(ui, {
+            let ui=ui.clone();
+            move |w: &mut Window| w.hide(&ui)
+        });
+        win.set_margined(ui,true);
+        win.set_child(ui,vbox);
+        win.show(ui);
     }
 
-    pub fn editor_info(&mut self,s: &str) {
-        self.info.set_text(&self.ui,s);
+    // Everything known about the connected device, as pasted in support tickets.
+    // Kept in English whatever the UI language, for whoever reads the ticket.
+    fn device_report(&self) -> String {
+        let dev=&self.root.spec[self.aspec];
+        let mut r=vec!();
+        r.push(format!("Port: {}",self.port));
+        r.push(format!("Interface: {}",ports::find(&self.port).map( |p| ports::port_info(&p) ).unwrap_or_else( || "not present".into() )));
+        match &self.device_info {
+            Some(i) => {
+                r.push(format!("Banner: {}",i.banner));
+                r.push(format!("Name: {}",i.name));
+                r.push(format!("Build: {}",i.build));
+            },
+            None => r.push("Banner: not read".into()),
+        }
+        r.push(format!("Signature: {} (product {}, model {}, version {})",dev.signature,dev.signature.product,dev.signature.model,dev.signature.version));
+        r.push(format!("Spec: {}",dev.title.en()));
+        let state=match self.link {
+            Link::Connected => "connected",
+            Link::Disconnected => "disconnected",
+            Link::Lost => "lost",
+            Link::Reconnecting => "reconnecting",
+        };
+        match &self.connected_at {
+            Some((at,since)) if self.link == Link::Connected => {
+                let s=since.elapsed().as_secs();
+                r.push(format!("Connection: {} since {} ({}h {:02}m {:02}s)",state,at,s/3600,s/60%60,s%60));
+            },
+            _ => r.push(format!("Connection: {}",state)),
+        }
+        if self.serial.is_some() {
+            let st=&self.stats;
+            let avg=st.average().map( |a| format!("{} ms",a.as_millis()) ).unwrap_or_else( || "-".into() );
+            r.push(format!("Commands: {} sent, {} failed, average latency {}",st.count,st.errors,avg));
+        } else {
+            r.push("Commands: not connected".into());
+        }
+        r.push(format!("boros_gui: {}",env!("CARGO_PKG_VERSION")));
+        r.join("\n")
     }
-    pub fn read_config(&mut self) {
-        if let Some(ser) = &mut self.serial {
-            
-            if let Ok(config) = ser.get_config() {
-                for (id,val) in config {
-                    if let Some(item)=self.root.spec[self.aspec].find(id.as_str()) {
-                        item.from_device(&self.ui, val.as_str())
-                    }
+
+    pub fn show_device_info(&mut self) {
+        let ui=&self.ui;
+        let text=self.device_report();
+        let mut win=Window::new(ui,&trf("Device information - {}",&[&self.port]),520,320,WindowType::NoMenubar);
+        let mut report=MultilineEntry::new(ui);
+        report.set_value(ui,&text);
+
+        let mut copy=Button::new(ui,tr("Copy to clipboard"));
+        let mut close=Button::new(ui,tr("Close"));
+        copy.on_clicked(ui, {
+            let ui=ui.clone();
+            let w=win.clone();
+            let report=report.clone();
+            move |_| {
+                if let Err(e)=clipboard::copy(&report.value(&ui)) {
+                    w.modal_err(&ui,tr("Clipboard"),&trf("{}.\nSelect the text and copy it by hand.",&[&e]));
+                }
+            }
+        });
+        close.on_clicked(ui, {
+            let ui=ui.clone();
+            let mut w=win.clone();
+            move |_| w.hide(&ui)
+        });
+
+        let mut vbox=VerticalBox::new(ui);
+        vbox.set_padded(ui,true);
+        vbox.append(ui,report,LayoutStrategy::Stretchy);
+        let mut bbox=HorizontalBox::new(ui);
+        bbox.set_padded(ui,true);
+        bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+        bbox.append(ui,close,LayoutStrategy::Compact);
+        bbox.append(ui,copy,LayoutStrategy::Compact);
+        vbox.append(ui,bbox,LayoutStrategy::Compact);
+        win.on_closing(ui, {
+            let ui=ui.clone();
+            move |w: &mut Window| w.hide(&ui)
+        });
+        win.set_margined(ui,true);
+        win.set_child(ui,vbox);
+        win.show(ui);
+    }
+
+    // Firmware upgrade hints for the device being edited
+    fn advice_text(&self) -> (String,String) {
+        let dev=&self.root.spec[self.aspec];
+        let mut short=vec!();
+        let mut long=String::new();
+        if let Some(min)=&dev.release.min_gui {
+            if !gui_supports(min) {
+                short.push(trf("This firmware needs boros_gui {} or newer.",&[&min]));
+            }
+        }
+        let newer=self.root.newer(&dev.signature);
+        if let Some(last)=newer.last() {
+            short.push(trf("Firmware V{} is available (device has V{}).",&[&last.signature.version,&dev.signature.version]));
+            let mut prev=&dev.signature;
+            for d in &newer {
+                long.push_str(&format!("V{} - {}\n{}\n",d.signature.version,d.title,d.release.changelog));
+                for r in self.root.upgrade_rules(prev,&d.signature) {
+                    long.push_str(&format!("  * {}\n",reports::rule(&r)));
                 }
-                self.editor_info("Config readed from device!");
-            } else {
-                self.editor_info("Failed to read configuration from device");
+                long.push('\n');
+                prev=&d.signature;
             }
         }
+        (short.join(" "),long)
     }
 
-    pub fn save_config(&mut self) {
-        if let Some(ser) = &mut self.serial {
-            for sec in &self.root.spec[self.aspec].sections {
-                for item in sec.items.iter() {
-                    let (valid,caption) = item.validate(&self.ui);
-                    if valid {
-                        let ser_cmd=item.to_device(&self.ui);
-                        if ser.do_cmd(&ser_cmd).is_err() {
-                            let msg=format!("The field '{}' in tab '{}' can't be written into the device",caption,sec.name);
-                            self.win.modal_err(&self.ui,"Field invalid",msg.as_str());
-                            self.editor_info("¡¡¡ Error writing cofiguration");
-                            return;
-                        }
-                    } else {
-                        let msg=format!("The field '{}' in tab '{}' is not valid. Check format and length.",caption,sec.name);
-                        self.win.modal_err(&self.ui,"Field invalid",msg.as_str());
-                        self.editor_info("¡¡¡ Invalid fields");
-                        return;
+    // Uploads the firmware on the worker, reporting progress in the status
+    // line; the new device comes back as Flashed
+    pub fn flash(&mut self,path: &Path) {
+        if !self.connected() {
+            return;
+        }
+        let flash_size=self.root.spec[self.aspec].serial.unwrap_or_default().flash_size;
+        let image=match Image::load(path,flash_size) {
+            Ok(i) => i,
+            Err(e) => {
+                self.win.modal_err(&self.ui,tr("Firmware"),&trf("Can't load {}: {}",&[&path.display(),&e]));
+                self.editor_info(tr("Firmware not flashed"));
+                return;
+            }
+        };
+        let before=match self.current_values() {
+            Ok(v) => v,
+            Err(msg) => {
+                self.invalid_fields(tr("Firmware"),&msg);
+                self.editor_info(tr("Firmware not flashed"));
+                return;
+            }
+        };
+        self.before_flash=Some(before);
+        let known: Vec<Signature>=self.root.spec.iter().map( |d| d.signature.clone() ).collect();
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            // Progress in steps of 10% so the status line keeps up
+            let mut last=usize::MAX;
+            let mut progress=|stage: &str,n: usize,total: usize| {
+                let step=n*10/total.max(1);
+                if step != last {
+                    last=step;
+                    let text=if stage == "Verifying" { tr("Verifying firmware, page {} of {}") } else { tr("Writing firmware, page {} of {}") };
+                    let _=c.send(Actions::EditorInfo(trf(text,&[&n,&total])));
+                }
+            };
+            let res=match ser.flash(&image,&mut progress) {
+                Ok(_) if ser.connect() => ser.get_info().map_err( |e| e.to_string() ),
+                Ok(_) => Err(tr("Firmware written but the device did not answer after reset").into()),
+                Err(e) => Err(trf("Flashing failed: {}",&[&e])),
+            };
+            // Firmware missing from the spec: ask it to describe itself
+            let res=res.map( |info| {
+                let dev=if known.contains(&info.signature) { None } else { ser.describe_device(&info.signature).ok() };
+                (info,dev)
+            });
+            let _=c.send(Actions::Flashed(res));
+        });
+    }
+
+    fn flashed(&mut self,res: std::result::Result<(DeviceInfo,Option<Device>),String>) {
+        let before=self.before_flash.take().unwrap_or_default();
+        match res {
+            Ok((info,described)) => {
+                let sig=info.signature.clone();
+                self.device_info=Some(info);
+                self.spec_asked=false;
+                let mut n=self.check_signature(&sig);
+                if let (usize::MAX,Some(dev))=(n,described) {
+                    n=self.root.add(dev);
+                }
+                if n < usize::MAX {
+                    let old=&self.root.spec[self.aspec].signature;
+                    if old.same_model(&sig) && old.version < sig.version {
+                        let mut report=Report::default();
+                        let values=migrate::apply(&self.root.upgrade_rules(old,&sig),before,&mut report);
+                        self.pending=Some((values,report));
                     }
+                    self.show(n);
+                    self.editor_info(&trf("Firmware flashed and verified, device is now {}",&[&sig]));
+                } else {
+                    self.win.modal_err(&self.ui,tr("Firmware"),&trf("The new firmware reports {}, which is not in the spec",&[&sig]));
+                    self.editor_info(tr("Unknown device signature after flashing"));
                 }
+            },
+            Err(e) => {
+                self.win.modal_err(&self.ui,tr("Firmware"),&e);
+                self.editor_info(tr("¡¡¡ Firmware update failed"));
             }
-            self.editor_info("Config written to device!");
         }
+    }
 
+    // Reads the device config on the worker; the result comes back as ConfigRead
+    pub fn read_config(&mut self) {
+        if !self.connected() {
+            return;
+        }
+        let dev=&self.root.spec[self.aspec];
+        let describe=if self.spec_asked || dev.described { None } else { Some(dev.signature.clone()) };
+        self.spec_asked=true;
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            if let Some(sig)=describe {
+                let _=c.send(Actions::FirmwareSpec(ser.get_spec(&sig).ok()));
+            }
+            let _=c.send(Actions::ConfigRead(ser.get_entries().map_err( |e| e.to_string() )));
+        });
+    }
+
+    fn config_read(&mut self,res: std::result::Result<Vec<ConfigEntry>,String>) {
+        if let Ok(entries) = res {
+            let config=ConfigEntry::values(&entries);
+            self.check_drift(&entries);
+            self.entries=entries;
+            for (id,val) in &config {
+                self.set_field(id,val);
+            }
+            self.record(config,"read");
+            self.editor_info(tr("Config read from the device"));
+            if let Some((values,mut report))=self.pending.take() {
+                self.apply_values(values,&mut report);
+                let msg=trf("The previous configuration was carried over to the new firmware.\n{}\nReview it and press 'Write configuration' to store it.",&[&reports::migration(&report)]);
+                self.win.modal_msg(&self.ui,tr("Configuration migrated"),&msg);
+                self.editor_info(tr("Migrated configuration not yet written"));
+            }
+            if let Some(name)=self.profile.take() {
+                self.apply_profile(&name);
+            }
+        } else {
+            self.editor_info(tr("Failed to read configuration from device"));
+        }
+    }
+
+    // Compares the spec with what the firmware reported and updates the banner
+    fn check_drift(&mut self,entries: &[ConfigEntry]) {
+        let dev=&self.root.spec[self.aspec];
+        self.drift=Drift::check(dev,entries);
+        if let Some(fw)=&self.firmware_spec {
+            self.drift.check_ranges(dev,fw);
+        }
+        let ui=&self.ui;
+        if self.drift.is_empty() {
+            if let Some(b)=&mut self.drift_box { b.hide(ui) }
+        } else {
+            let text=trf("⚠ The firmware differs from the spec: {}",&[&reports::drift_summary(&self.drift)]);
+            self.drift_label.set_text(ui,&text);
+            if let Some(b)=&mut self.drift_box { b.show(ui) }
+        }
+    }
+
+    pub fn show_drift(&mut self) {
+        let sig=&self.root.spec[self.aspec].signature;
+        let msg=trf("Spec of {} compared with the last read from the device.\n\n{}",&[&sig,&reports::drift_details(&self.drift)]);
+        self.win.modal_msg(&self.ui,tr("Spec drift"),&msg);
+    }
+
+    // Sends every item to the device on the worker; the result comes back as ConfigWritten
+    pub fn save_config(&mut self) {
+        if !self.connected() {
+            return;
+        }
+        if let Err(msg)=self.store_widgets() {
+            return self.invalid_fields(tr("Field invalid"),&msg);
+        }
+        // Only a write every item of which the device acknowledged is recorded
+        let dev=self.root.spec[self.aspec].clone();
+        let values=dev.values();
+        let c=self.cmd.clone();
+        self.post(move |ser| {
+            let res=ser.write_config(&dev,&values).map( |_| values ).map_err( |e| trf("The configuration can't be written into the device: {}",&[&e]) );
+            let _=c.send(Actions::ConfigWritten(res));
+        });
+    }
+
+    fn config_written(&mut self,res: std::result::Result<Vec<(String,String)>,String>) {
+        match res {
+            Ok(values) => {
+                self.record(values,"write");
+                self.editor_info(tr("Config written to the device"));
+            },
+            Err(msg) => {
+                self.win.modal_err(&self.ui,tr("Field invalid"),msg.as_str());
+                self.editor_info(tr("¡¡¡ Error writing the configuration"));
+            }
+        }
     }
 
     pub fn show(&mut self,n: usize) {
         self.aspec=n;
-        let model= &mut self.root.spec[n];
+        reports::localize(&mut self.root.spec[n]);
+        let (advice,changes)=self.advice_text();
+        self.advice.set_text(&self.ui,&advice);
+        let model= &self.root.spec[n];
         let ui=&self.ui;
-        self.win.set_title(ui,model.title.as_str());
+        self.win.set_title(ui,&format!("{} - {}",model.title,self.port));
         let mut tabs= TabGroup::new(ui);
+        self.widgets.clear();
         
-        for sec in &mut model.sections {
+        for (sn,sec) in model.sections.iter().enumerate() {
             let mut tab=VerticalBox::new(ui);
             tab.set_padded(ui,true);
-            for i in &mut sec.items {
-                tab.append(ui,i.build_control(ui),LayoutStrategy::Stretchy);
+            for i in &sec.items {
+                let (w,row)=Widget::build(i,ui,&self.cmd);
+                self.widgets.push(w);
+                tab.append(ui,row,LayoutStrategy::Stretchy);
             }
-            let mut help = Button::new(ui,"Help");
+            let mut help = Button::new(ui,tr("Help"));
+            let mut sdef = Button::new(ui,tr("Tab defaults"));
             tab.append(ui,HorizontalSeparator::new(ui),LayoutStrategy::Compact);
             
             help.on_clicked(ui, {
@@ -371,68 +1414,212 @@ impl Editor {
                 let w=self.win.clone();
                 let h=sec.help.clone();
                 move |_| {
-                    w.modal_msg(&ui,"Help",h.as_str());
+                    w.modal_msg(&ui,tr("Help"),h.get());
                 }
             });
 
-            tab.append(ui,help,LayoutStrategy::Compact);
-            let n=tabs.append(ui,&sec.name,tab);
+            sdef.on_clicked(ui, {
+                let c=self.cmd.clone();
+                move |_| {
+                    let _=c.send(Actions::DefaultSection(sn));
+                }
+            });
+
+            let mut hb=HorizontalBox::new(ui);
+            hb.set_padded(ui,true);
+            hb.append(ui,help,LayoutStrategy::Compact);
+            if sec.items.iter().any( |i| i.has_default() ) {
+                hb.append(ui,sdef,LayoutStrategy::Compact);
+            }
+            tab.append(ui,hb,LayoutStrategy::Compact);
+            let n=tabs.append(ui,sec.name.get(),tab);
             tabs.set_margined(ui,n-1,true);
         }
 
         let mut vbox=VerticalBox::new(ui);
         vbox.set_padded(ui,true);
         vbox.append(ui,self.info.clone(),LayoutStrategy::Compact);
+        if !advice.is_empty() {
+            let mut hb=HorizontalBox::new(ui);
+            hb.set_padded(ui,true);
+            hb.append(ui,self.advice.clone(),LayoutStrategy::Stretchy);
+            if !changes.is_empty() {
+                let mut news=Button::new(ui,tr("What's new"));
+                news.on_clicked(ui, {
+                    let ui=ui.clone();
+                    let w=self.win.clone();
+                    move |_| w.modal_msg(&ui,tr("Firmware updates"),&changes)
+                });
+                hb.append(ui,news,LayoutStrategy::Compact);
+            }
+            vbox.append(ui,hb,LayoutStrategy::Compact);
+        }
+        let mut hb=HorizontalBox::new(ui);
+        hb.set_padded(ui,true);
+        hb.append(ui,self.drift_label.clone(),LayoutStrategy::Stretchy);
+        let mut details=Button::new(ui,tr("Drift report"));
+        details.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::ShowDrift);
+            }
+        });
+        hb.append(ui,details,LayoutStrategy::Compact);
+        hb.hide(ui);
+        vbox.append(ui,hb.clone(),LayoutStrategy::Compact);
+        self.drift_box=Some(hb);
         vbox.append(ui,tabs,LayoutStrategy::Compact);
         let mut bbox=HorizontalBox::new(ui);
         bbox.set_padded(ui,true);
-        let mut quit=Button::new(ui,"Quit");
-        let mut reset=Button::new(ui,"Factory reset");
-        let mut read=Button::new(ui,"Read configuration");
-        let mut write=Button::new(ui,"Write configuration");
+        let mut quit=Button::new(ui,tr("Close"));
+        let mut devices=Button::new(ui,tr("Devices"));
+        let mut reset=Button::new(ui,tr("Factory reset"));
+        let mut restore=Button::new(ui,tr("Restore previous configuration"));
+        let mut defaults=Button::new(ui,tr("Defaults"));
+        let mut hist=Button::new(ui,tr("History"));
+        let mut about=Button::new(ui,tr("Device info"));
+        let mut comp=Button::new(ui,tr("Compare"));
+        let mut prof=Button::new(ui,tr("Profiles"));
+        let mut read=Button::new(ui,tr("Read configuration"));
+        let mut write=Button::new(ui,tr("Write configuration"));
+        let mut flash=Button::new(ui,tr("Flash firmware"));
+        let mut to_file=Button::new(ui,tr("Save to file"));
+        let mut from_file=Button::new(ui,tr("Load from file"));
         
-        quit.on_clicked(&ui, {
-            let ui = ui.clone();
+        quit.on_clicked(ui, {
+            let c=self.app.clone();
+            let id=self.id;
             move |_| {
-                ui.quit();
+                let _=c.send(Actions::CloseEditor(id));
+            }
+        });
+
+        devices.on_clicked(ui, {
+            let c=self.app.clone();
+            move |_| {
+                let _=c.send(Actions::ShowDevices);
             }
         });
 
         reset.on_clicked(ui, {
+            let ui = ui.clone();
             let c = self.cmd.clone();
             move |_| {
-                let _=c.send(Actions::EditorInfo("Factory reset...".into()));
-                let _=c.send(Actions::Reset);
-                let _=c.send(Actions::EditorInfo("Reading config...".into()));
-                let _=c.send(Actions::ReadConfig);
+                let c=c.clone();
+                dialogs::confirm(&ui,tr("Factory reset"),
+                    tr("Restore the factory settings of the device?\nDevice ID and radio addresses are erased.\nThe current configuration is backed up first."),
+                    move || {
+                        let _=c.send(Actions::EditorInfo(tr("Factory reset...").into()));
+                        let _=c.send(Actions::Reset);
+                    });
+            }
+        });
+        restore.on_clicked(ui, {
+            let c = self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::EditorInfo(tr("Restoring configuration...").into()));
+                let _=c.send(Actions::RestoreBackup);
+            }
+        });
+        prof.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::ShowProfiles);
+            }
+        });
+        comp.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::EditorInfo(tr("Reading config to compare...").into()));
+                let _=c.send(Actions::Compare);
+            }
+        });
+        hist.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::ShowHistory);
+            }
+        });
+        about.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::ShowDeviceInfo);
+            }
+        });
+        defaults.on_clicked(ui, {
+            let c=self.cmd.clone();
+            move |_| {
+                let _=c.send(Actions::DefaultAll);
             }
         });
         read.on_clicked(ui, {
             let c=self.cmd.clone();
             move |_| {
-                let _=c.send(Actions::EditorInfo("Reading config...".into()));
+                let _=c.send(Actions::EditorInfo(tr("Reading config...").into()));
                 let _=c.send(Actions::ReadConfig);
             }
         });
+        to_file.on_clicked(ui, {
+            let ui=ui.clone();
+            let w=self.win.clone();
+            let c=self.cmd.clone();
+            move |_| {
+                if let Some(p)=w.save_file(&ui) {
+                    let _=c.send(Actions::SaveFile(p));
+                }
+            }
+        });
+        from_file.on_clicked(ui, {
+            let ui=ui.clone();
+            let w=self.win.clone();
+            let c=self.cmd.clone();
+            move |_| {
+                if let Some(p)=w.open_file(&ui) {
+                    let _=c.send(Actions::LoadFile(p));
+                }
+            }
+        });
+        flash.on_clicked(ui, {
+            let ui=ui.clone();
+            let w=self.win.clone();
+            let c=self.cmd.clone();
+            move |_| {
+                if let Some(p)=w.open_file(&ui) {
+                    let _=c.send(Actions::EditorInfo(tr("Flashing firmware, do not unplug the device...").into()));
+                    let _=c.send(Actions::Flash(p));
+                }
+            }
+        });
         write.on_clicked(ui, {
           let c=self.cmd.clone();
           move |_| {
-              let _=c.send(Actions::EditorInfo("Writing configuration..".into()));
+              let _=c.send(Actions::EditorInfo(tr("Writing configuration...").into()));
               let _=c.send(Actions::SaveConfig);
           }
         });
 
         bbox.append(ui,quit,LayoutStrategy::Compact);
+        bbox.append(ui,devices,LayoutStrategy::Compact);
+        bbox.append(ui,self.link_button.clone(),LayoutStrategy::Compact);
         bbox.append(ui,reset,LayoutStrategy::Compact);
+        bbox.append(ui,restore,LayoutStrategy::Compact);
+        bbox.append(ui,defaults,LayoutStrategy::Compact);
         bbox.append(ui,read,LayoutStrategy::Compact);
         bbox.append(ui,write,LayoutStrategy::Compact);
+        bbox.append(ui,prof,LayoutStrategy::Compact);
+        bbox.append(ui,comp,LayoutStrategy::Compact);
+        bbox.append(ui,hist,LayoutStrategy::Compact);
+        bbox.append(ui,about,LayoutStrategy::Compact);
+        bbox.append(ui,to_file,LayoutStrategy::Compact);
+        bbox.append(ui,from_file,LayoutStrategy::Compact);
+        bbox.append(ui,flash,LayoutStrategy::Compact);
         
         vbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
         vbox.append(ui,bbox,LayoutStrategy::Compact);
 
         self.win.set_child(ui,vbox);
         self.win.show(ui);
-        let _=self.cmd.send(Actions::EditorInfo("Reading config...".into()));
+        let _=self.cmd.send(Actions::EditorInfo(tr("Reading config...").into()));
         let _ =self.cmd.send(Actions::ReadConfig);
     }
 
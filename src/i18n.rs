@@ -0,0 +1,504 @@
+use std::fmt::Display;
+
+use boros_core::lang;
+
+// UI texts keyed by the English text used in the code. A text missing from a
+// table is shown in English. Translations keep the `{}` of the English text
+// in the same order.
+pub fn tr(text: &'static str) -> &'static str {
+    let table=match lang::current() {
+        "es" => ES,
+        "de" => DE,
+        _ => return text,
+    };
+    table.iter().find( |(en,_)| *en==text ).map( |(_,t)| *t ).unwrap_or(text)
+}
+
+// `tr` with each `{}` replaced by the next argument
+pub fn trf(text: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts=tr(text).split("{}");
+    let mut out=parts.next().unwrap_or_default().to_string();
+    for (n,p) in parts.enumerate() {
+        if let Some(a)=args.get(n) {
+            out.push_str(&a.to_string());
+        }
+        out.push_str(p);
+    }
+    out
+}
+
+const ES: &[(&str,&str)] = &[
+    // Port window and device list
+    ("Boros devices","Dispositivos Boros"),
+    ("Tools","Herramientas"),
+    ("Preferences...","Preferencias..."),
+    ("Protocol transcript","Registro del protocolo"),
+    ("Serial interface","Interfaz serie"),
+    ("No serial interfaces","No hay interfaces serie"),
+    ("Select a serial interface","Seleccione una interfaz serie"),
+    ("Attach without reset","Conectar sin reiniciar"),
+    ("Auto-detect","Detectar"),
+    ("Open","Abrir"),
+    ("Quit","Salir"),
+    ("Error","Error"),
+    ("No serial interface selected","No se ha seleccionado ninguna interfaz serie"),
+    ("Found: {}","Encontrados: {}"),
+    (", not in spec",", no está en la especificación"),
+    ("No Boros device found","No se encontró ningún dispositivo Boros"),
+    ("Open devices","Dispositivos abiertos"),
+    ("No device open","Ningún dispositivo abierto"),
+    ("Show","Mostrar"),
+    ("Close","Cerrar"),
+    ("Copy configuration","Copiar configuración"),
+    ("Copy configuration to","Copiar configuración a"),
+    ("Select two different open devices","Seleccione dos dispositivos abiertos distintos"),
+    ("{} is not in the spec and its configuration can't be read: {}","{} no está en la especificación y no se puede leer su configuración: {}"),
+    ("Couldn't open the serial interface","No se pudo abrir la interfaz serie"),
+    ("The device did not answer with a prompt. Uncheck 'Attach without reset' to reboot it and retry.",
+     "El dispositivo no respondió con el prompt. Desmarque 'Conectar sin reiniciar' para reiniciarlo y vuelva a intentarlo."),
+    ("Couldn't retrieve a valid signature of the device","No se pudo obtener una firma válida del dispositivo"),
+    ("No","No"),
+    ("Yes","Sí"),
+
+    // Editor
+    ("Config editor - {}","Editor de configuración - {}"),
+    ("Ready","Listo"),
+    ("Default","Por defecto"),
+    ("nothing selected","nada seleccionado"),
+    ("Connect","Conectar"),
+    ("Disconnect","Desconectar"),
+    (" (disconnected)"," (desconectado)"),
+    (" (lost)"," (perdido)"),
+    (" (reconnecting)"," (reconectando)"),
+    ("Disconnected, press Connect to talk to the device again","Desconectado, pulse Conectar para volver a comunicar con el dispositivo"),
+    ("¡¡¡ Link lost, the port is gone. Waiting for the device to return...","¡¡¡ Conexión perdida, el puerto ha desaparecido. Esperando a que vuelva el dispositivo..."),
+    ("¡¡¡ Link lost ({}). Waiting for the device to return...","¡¡¡ Conexión perdida ({}). Esperando a que vuelva el dispositivo..."),
+    ("Port is back, reconnecting...","El puerto ha vuelto, reconectando..."),
+    ("Reconnected to {}, editor values kept","Reconectado a {}, se conservan los valores del editor"),
+    ("The device on {} now reports {} instead of {}.\nClose this editor and open the port again to edit it.",
+     "El dispositivo en {} ahora es {} en lugar de {}.\nCierre este editor y abra de nuevo el puerto para editarlo."),
+    ("Reconnect","Reconectar"),
+    ("Not reconnected, a different device answered","No reconectado, respondió otro dispositivo"),
+    ("Reconnection failed: {}. Press Connect to retry","Falló la reconexión: {}. Pulse Conectar para reintentar"),
+    ("Not connected, press Connect first","No conectado, pulse Conectar primero"),
+    ("Connecting...","Conectando..."),
+    ("Factory reset","Valores de fábrica"),
+    ("Factory reset...","Restaurando valores de fábrica..."),
+    ("Restore the factory settings of the device?\nDevice ID and radio addresses are erased.\nThe current configuration is backed up first.",
+     "¿Restaurar los valores de fábrica del dispositivo?\nSe borran el ID del dispositivo y las direcciones de radio.\nAntes se guarda una copia de la configuración actual."),
+    ("The configuration could not be backed up ({}), factory reset cancelled","No se pudo guardar la configuración ({}), se cancela la restauración de fábrica"),
+    ("The device reported no configuration to back up, factory reset cancelled","El dispositivo no devolvió ninguna configuración que guardar, se cancela la restauración de fábrica"),
+    ("Factory settings not done","Valores de fábrica no restaurados"),
+    ("The device is no longer connected","El dispositivo ya no está conectado"),
+    ("Factory settings done, backup in {}","Valores de fábrica restaurados, copia en {}"),
+    ("⚠ {} kept from the device","⚠ {} conservado del dispositivo"),
+    ("Factory settings done, backup kept in memory only","Valores de fábrica restaurados, copia solo en memoria"),
+    ("Factory settings failed.","Falló la restauración de fábrica."),
+    ("Restore","Restaurar"),
+    ("Restore previous configuration","Restaurar configuración anterior"),
+    ("Restoring configuration...","Restaurando configuración..."),
+    ("There is no configuration backed up by a factory reset in this session.","No hay ninguna configuración guardada por una restauración de fábrica en esta sesión."),
+    ("Field invalid","Campo no válido"),
+    ("¡¡¡ Invalid fields","¡¡¡ Campos no válidos"),
+    ("The field '{}' in tab '{}' is not valid: {}.\nCheck format and length.","El campo '{}' de la pestaña '{}' no es válido: {}.\nCompruebe el formato y la longitud."),
+    ("The configuration can't be written into the device: {}","La configuración no se puede escribir en el dispositivo: {}"),
+    ("Default value restored for '{}', not yet written","Valor por defecto restaurado para '{}', aún no escrito"),
+    ("Defaults restored in '{}', not yet written","Valores por defecto restaurados en '{}', aún no escritos"),
+    ("All defaults restored, not yet written","Todos los valores por defecto restaurados, aún no escritos"),
+    ("Defaults","Valores por defecto"),
+    ("Tab defaults","Valores por defecto de la pestaña"),
+    ("Help","Ayuda"),
+    ("Save","Guardar"),
+    ("Load","Cargar"),
+    ("Save to file","Guardar en archivo"),
+    ("Load from file","Cargar de archivo"),
+    ("Config saved to {}","Configuración guardada en {}"),
+    ("Can't write {}: {}","No se puede escribir {}: {}"),
+    ("Can't read {}: {}","No se puede leer {}: {}"),
+    ("Can't load {}: {}","No se puede cargar {}: {}"),
+    ("Config loaded from file, not yet written to the device","Configuración cargada del archivo, aún no escrita en el dispositivo"),
+    ("Config copied from {}, not yet written to the device","Configuración copiada de {}, aún no escrita en el dispositivo"),
+    ("{} was saved from a {} device and can't be applied to {}","{} se guardó de un dispositivo {} y no se puede aplicar a {}"),
+    ("{} was saved from {} and converted for {}.\n{}","{} se guardó de {} y se convirtió para {}.\n{}"),
+    ("Configuration migrated","Configuración migrada"),
+    ("The previous configuration was carried over to the new firmware.\n{}\nReview it and press 'Write configuration' to store it.",
+     "La configuración anterior se ha trasladado al nuevo firmware.\n{}\nRevísela y pulse 'Escribir configuración' para guardarla."),
+    ("Migrated configuration not yet written","Configuración migrada aún no escrita"),
+    ("Read configuration","Leer configuración"),
+    ("Write configuration","Escribir configuración"),
+    ("Reading config...","Leyendo configuración..."),
+    ("Writing configuration...","Escribiendo configuración..."),
+    ("Config read from the device","Configuración leída del dispositivo"),
+    ("Config written to the device","Configuración escrita en el dispositivo"),
+    ("Failed to read configuration from device","No se pudo leer la configuración del dispositivo"),
+    ("¡¡¡ Error writing the configuration","¡¡¡ Error al escribir la configuración"),
+    ("⚠ The firmware differs from the spec: {}","⚠ El firmware difiere de la especificación: {}"),
+    ("Spec of {} compared with the last read from the device.\n\n{}","Especificación de {} comparada con la última lectura del dispositivo.\n\n{}"),
+    ("Spec drift","Diferencias con la especificación"),
+    ("Drift report","Informe de diferencias"),
+    ("{} field(s) not in the spec","{} campo(s) fuera de la especificación"),
+    ("{} spec field(s) not reported","{} campo(s) de la especificación no recibidos"),
+    ("{} caption(s) differ","{} título(s) distintos"),
+    ("{} value(s) out of the spec limits","{} valor(es) fuera de los límites de la especificación"),
+    ("{} limit(s) differ","{} límite(s) distintos"),
+    ("The firmware matches the spec.","El firmware coincide con la especificación."),
+    ("Reported by the device but missing from the spec (not editable):","Enviados por el dispositivo pero ausentes de la especificación (no editables):"),
+    ("In the spec but not reported by the device (shown with defaults):","En la especificación pero no enviados por el dispositivo (se muestran los valores por defecto):"),
+    ("Captions that differ (spec / firmware):","Títulos distintos (especificación / firmware):"),
+    ("Values the spec does not accept (range or format changed?):","Valores que la especificación no acepta (¿cambió el rango o el formato?):"),
+    ("Limits that differ (spec / firmware):","Límites distintos (especificación / firmware):"),
+    ("up to {} bytes","hasta {} bytes"),
+    ("up to {} characters","hasta {} caracteres"),
+    ("choices {}","opciones {}"),
+    ("No changes were needed.","No hizo falta ningún cambio."),
+    ("Converted: {}","Convertidos: {}"),
+    ("Dropped: {}","Descartados: {}"),
+    ("{} (not in spec)","{} (no está en la especificación)"),
+    ("'{}' is renamed to '{}'","'{}' pasa a llamarse '{}'"),
+    ("'{}' is removed","'{}' se elimina"),
+    ("'{}' values change ({})","'{}' cambia de valores ({})"),
+    ("'{}' is rescaled (x{} {})","'{}' cambia de escala (x{} {})"),
+    ("'{}' is set to {}","'{}' se fija en {}"),
+    ("{} (generic editor)","{} (editor genérico)"),
+    ("{} (described by the firmware)","{} (descrito por el firmware)"),
+    ("Other","Otros"),
+    ("Parameters","Parámetros"),
+    ("Parameters reported by the device but not described by its spec. They are edited as plain text.",
+     "Parámetros enviados por el dispositivo que su especificación no describe. Se editan como texto."),
+    ("This device is not in the spec. Every parameter is edited as text,\ncheck the firmware documentation for valid values.",
+     "Este dispositivo no está en la especificación. Todos los parámetros se editan como texto,\nconsulte la documentación del firmware para conocer los valores válidos."),
+    ("Devices","Dispositivos"),
+
+    // Compare, profiles and history
+    ("Compare","Comparar"),
+    ("Compare {}","Comparar {}"),
+    ("Reading config to compare...","Leyendo configuración para comparar..."),
+    ("Can't read the device configuration: {}","No se puede leer la configuración del dispositivo: {}"),
+    ("Field","Campo"),
+    ("Device","Dispositivo"),
+    ("Editor","Editor"),
+    ("Use","Usar"),
+    ("Apply to editor","Aplicar al editor"),
+    ("{} field(s) differ between the device and the editor (≠). '*' marks values that differ from the spec default.",
+     "{} campo(s) difieren entre el dispositivo y el editor (≠). '*' marca los valores distintos del valor por defecto."),
+    ("{} field(s) updated from the comparison, not yet written","{} campo(s) actualizados desde la comparación, aún no escritos"),
+    ("Profiles","Perfiles"),
+    ("Profiles for {}","Perfiles de {}"),
+    ("Profile '{}'","Perfil '{}'"),
+    ("Profile '{}' applied ({} fields), not yet written","Perfil '{}' aplicado ({} campos), aún no escrito"),
+    ("Profile '{}' saved with {} fields","Perfil '{}' guardado con {} campos"),
+    ("Profile '{}' deleted","Perfil '{}' borrado"),
+    ("Can't load profile '{}': {}","No se puede cargar el perfil '{}': {}"),
+    ("Can't save profile '{}': {}","No se puede guardar el perfil '{}': {}"),
+    ("Can't delete profile '{}': {}","No se puede borrar el perfil '{}': {}"),
+    ("Apply","Aplicar"),
+    ("Delete","Borrar"),
+    ("Save current as","Guardar la actual como"),
+    ("Delete the profile '{}'?","¿Borrar el perfil '{}'?"),
+    ("Type a name for the new profile","Escriba un nombre para el nuevo perfil"),
+    ("New profiles keep the fields that differ from the defaults, except the Device ID.","Los perfiles nuevos guardan los campos distintos de los valores por defecto, salvo el ID del dispositivo."),
+    ("History","Historial"),
+    ("History of {}","Historial de {}"),
+    ("No configuration of this device has been stored yet.","Todavía no se ha guardado ninguna configuración de este dispositivo."),
+    ("The snapshot of {}","La instantánea del {}"),
+    ("No differences","Sin diferencias"),
+    ("From","Desde"),
+    ("To","Hasta"),
+    ("Restore 'To' snapshot","Restaurar la instantánea 'Hasta'"),
+    ("Restoring snapshot...","Restaurando instantánea..."),
+
+    // Device information and firmware
+    ("Device info","Información del dispositivo"),
+    ("Device information - {}","Información del dispositivo - {}"),
+    ("Copy to clipboard","Copiar al portapapeles"),
+    ("Clipboard","Portapapeles"),
+    ("{}.\nSelect the text and copy it by hand.","{}.\nSeleccione el texto y cópielo a mano."),
+    ("What's new","Novedades"),
+    ("Firmware updates","Actualizaciones de firmware"),
+    ("This firmware needs boros_gui {} or newer.","Este firmware necesita boros_gui {} o posterior."),
+    ("Firmware V{} is available (device has V{}).","Hay disponible el firmware V{} (el dispositivo tiene V{})."),
+    ("Firmware","Firmware"),
+    ("Flash firmware","Grabar firmware"),
+    ("Flashing firmware, do not unplug the device...","Grabando firmware, no desconecte el dispositivo..."),
+    ("Firmware not flashed","Firmware no grabado"),
+    ("Firmware written but the device did not answer after reset","Firmware grabado, pero el dispositivo no respondió tras el reinicio"),
+    ("Flashing failed: {}","Falló la grabación: {}"),
+    ("Writing firmware, page {} of {}","Grabando firmware, página {} de {}"),
+    ("Verifying firmware, page {} of {}","Verificando firmware, página {} de {}"),
+    ("Firmware flashed and verified, device is now {}","Firmware grabado y verificado, el dispositivo es ahora {}"),
+    ("The new firmware reports {}, which is not in the spec","El nuevo firmware indica {}, que no está en la especificación"),
+    ("Unknown device signature after flashing","Firma de dispositivo desconocida tras la grabación"),
+    ("¡¡¡ Firmware update failed","¡¡¡ Falló la actualización del firmware"),
+
+    // Preferences
+    ("Preferences","Preferencias"),
+    ("General","General"),
+    ("Serial","Serie"),
+    ("Advanced","Avanzado"),
+    ("Cancel","Cancelar"),
+    ("Default port","Puerto por defecto"),
+    ("Connect to the default port at startup","Conectar al puerto por defecto al iniciar"),
+    ("Reconnect when an unplugged device returns","Reconectar cuando vuelva un dispositivo desenchufado"),
+    ("Write a protocol transcript","Guardar un registro del protocolo"),
+    ("Transcript file","Archivo de registro"),
+    ("Language (from the next start)","Idioma (a partir del próximo inicio)"),
+    ("System language","Idioma del sistema"),
+    ("Spec files, one per line, used from the next start.\nThe built-in spec is used if none can be read.",
+     "Archivos de especificación, uno por línea, usados a partir del próximo inicio.\nSi no se puede leer ninguno se usa la especificación integrada."),
+    ("Empty fields use the values of the spec.","Los campos vacíos usan los valores de la especificación."),
+    ("Baud rate","Velocidad (baudios)"),
+    ("Data bits","Bits de datos"),
+    ("Parity","Paridad"),
+    ("Stop bits","Bits de parada"),
+    ("Flow control","Control de flujo"),
+    ("Reset line","Línea de reinicio"),
+    ("Reset pulse (ms)","Pulso de reinicio (ms)"),
+    ("Boot delay (ms)","Espera de arranque (ms)"),
+    ("Bootloader baud rate","Velocidad del bootloader"),
+    ("Read timeout (ms)","Tiempo de espera de lectura (ms)"),
+    ("Pause before a command (ms)","Pausa antes de cada comando (ms)"),
+    ("Wait for the answer (ms)","Espera de la respuesta (ms)"),
+    ("Port window width","Ancho de la ventana de puertos"),
+    ("Port window height","Alto de la ventana de puertos"),
+    ("Editor width","Ancho del editor"),
+    ("Editor height","Alto del editor"),
+    ("Window sizes apply to windows opened from the next start.","Los tamaños se aplican a las ventanas abiertas a partir del próximo inicio."),
+];
+
+const DE: &[(&str,&str)] = &[
+    // Port window and device list
+    ("Boros devices","Boros-Geräte"),
+    ("Tools","Werkzeuge"),
+    ("Preferences...","Einstellungen..."),
+    ("Protocol transcript","Protokollmitschnitt"),
+    ("Serial interface","Serielle Schnittstelle"),
+    ("No serial interfaces","Keine seriellen Schnittstellen"),
+    ("Select a serial interface","Serielle Schnittstelle auswählen"),
+    ("Attach without reset","Ohne Neustart verbinden"),
+    ("Auto-detect","Suchen"),
+    ("Open","Öffnen"),
+    ("Quit","Beenden"),
+    ("Error","Fehler"),
+    ("No serial interface selected","Keine serielle Schnittstelle ausgewählt"),
+    ("Found: {}","Gefunden: {}"),
+    (", not in spec",", nicht in der Spezifikation"),
+    ("No Boros device found","Kein Boros-Gerät gefunden"),
+    ("Open devices","Geöffnete Geräte"),
+    ("No device open","Kein Gerät geöffnet"),
+    ("Show","Anzeigen"),
+    ("Close","Schließen"),
+    ("Copy configuration","Konfiguration kopieren"),
+    ("Copy configuration to","Konfiguration kopieren nach"),
+    ("Select two different open devices","Zwei verschiedene geöffnete Geräte auswählen"),
+    ("{} is not in the spec and its configuration can't be read: {}","{} ist nicht in der Spezifikation und seine Konfiguration kann nicht gelesen werden: {}"),
+    ("Couldn't open the serial interface","Die serielle Schnittstelle konnte nicht geöffnet werden"),
+    ("The device did not answer with a prompt. Uncheck 'Attach without reset' to reboot it and retry.",
+     "Das Gerät hat nicht mit dem Prompt geantwortet. 'Ohne Neustart verbinden' abwählen, um es neu zu starten, und erneut versuchen."),
+    ("Couldn't retrieve a valid signature of the device","Keine gültige Signatur vom Gerät erhalten"),
+    ("No","Nein"),
+    ("Yes","Ja"),
+
+    // Editor
+    ("Config editor - {}","Konfigurationseditor - {}"),
+    ("Ready","Bereit"),
+    ("Default","Standard"),
+    ("nothing selected","nichts ausgewählt"),
+    ("Connect","Verbinden"),
+    ("Disconnect","Trennen"),
+    (" (disconnected)"," (getrennt)"),
+    (" (lost)"," (verloren)"),
+    (" (reconnecting)"," (verbindet neu)"),
+    ("Disconnected, press Connect to talk to the device again","Getrennt, 'Verbinden' drücken, um wieder mit dem Gerät zu sprechen"),
+    ("¡¡¡ Link lost, the port is gone. Waiting for the device to return...","¡¡¡ Verbindung verloren, der Port ist weg. Warte auf die Rückkehr des Geräts..."),
+    ("¡¡¡ Link lost ({}). Waiting for the device to return...","¡¡¡ Verbindung verloren ({}). Warte auf die Rückkehr des Geräts..."),
+    ("Port is back, reconnecting...","Port ist zurück, verbinde neu..."),
+    ("Reconnected to {}, editor values kept","Neu verbunden mit {}, Werte des Editors beibehalten"),
+    ("The device on {} now reports {} instead of {}.\nClose this editor and open the port again to edit it.",
+     "Das Gerät an {} meldet jetzt {} statt {}.\nDiesen Editor schließen und den Port erneut öffnen, um es zu bearbeiten."),
+    ("Reconnect","Neu verbinden"),
+    ("Not reconnected, a different device answered","Nicht neu verbunden, ein anderes Gerät hat geantwortet"),
+    ("Reconnection failed: {}. Press Connect to retry","Neuverbindung fehlgeschlagen: {}. 'Verbinden' drücken, um es erneut zu versuchen"),
+    ("Not connected, press Connect first","Nicht verbunden, zuerst 'Verbinden' drücken"),
+    ("Connecting...","Verbinde..."),
+    ("Factory reset","Werkseinstellungen"),
+    ("Factory reset...","Stelle Werkseinstellungen her..."),
+    ("Restore the factory settings of the device?\nDevice ID and radio addresses are erased.\nThe current configuration is backed up first.",
+     "Werkseinstellungen des Geräts wiederherstellen?\nGeräte-ID und Funkadressen werden gelöscht.\nDie aktuelle Konfiguration wird vorher gesichert."),
+    ("The configuration could not be backed up ({}), factory reset cancelled","Die Konfiguration konnte nicht gesichert werden ({}), Werkseinstellungen abgebrochen"),
+    ("The device reported no configuration to back up, factory reset cancelled","Das Gerät hat keine Konfiguration zum Sichern geliefert, Werkseinstellungen abgebrochen"),
+    ("Factory settings not done","Werkseinstellungen nicht hergestellt"),
+    ("The device is no longer connected","Das Gerät ist nicht mehr verbunden"),
+    ("Factory settings done, backup in {}","Werkseinstellungen hergestellt, Sicherung in {}"),
+    ("⚠ {} kept from the device","⚠ {} vom Gerät übernommen"),
+    ("Factory settings done, backup kept in memory only","Werkseinstellungen hergestellt, Sicherung nur im Speicher"),
+    ("Factory settings failed.","Werkseinstellungen fehlgeschlagen."),
+    ("Restore","Wiederherstellen"),
+    ("Restore previous configuration","Vorherige Konfiguration wiederherstellen"),
+    ("Restoring configuration...","Stelle Konfiguration wieder her..."),
+    ("There is no configuration backed up by a factory reset in this session.","In dieser Sitzung wurde keine Konfiguration vor Werkseinstellungen gesichert."),
+    ("Field invalid","Ungültiges Feld"),
+    ("¡¡¡ Invalid fields","¡¡¡ Ungültige Felder"),
+    ("The field '{}' in tab '{}' is not valid: {}.\nCheck format and length.","Das Feld '{}' im Reiter '{}' ist ungültig: {}.\nFormat und Länge prüfen."),
+    ("The configuration can't be written into the device: {}","Die Konfiguration kann nicht ins Gerät geschrieben werden: {}"),
+    ("Default value restored for '{}', not yet written","Standardwert für '{}' wiederhergestellt, noch nicht geschrieben"),
+    ("Defaults restored in '{}', not yet written","Standardwerte in '{}' wiederhergestellt, noch nicht geschrieben"),
+    ("All defaults restored, not yet written","Alle Standardwerte wiederhergestellt, noch nicht geschrieben"),
+    ("Defaults","Standardwerte"),
+    ("Tab defaults","Standardwerte des Reiters"),
+    ("Help","Hilfe"),
+    ("Save","Speichern"),
+    ("Load","Laden"),
+    ("Save to file","In Datei speichern"),
+    ("Load from file","Aus Datei laden"),
+    ("Config saved to {}","Konfiguration gespeichert in {}"),
+    ("Can't write {}: {}","{} kann nicht geschrieben werden: {}"),
+    ("Can't read {}: {}","{} kann nicht gelesen werden: {}"),
+    ("Can't load {}: {}","{} kann nicht geladen werden: {}"),
+    ("Config loaded from file, not yet written to the device","Konfiguration aus Datei geladen, noch nicht ins Gerät geschrieben"),
+    ("Config copied from {}, not yet written to the device","Konfiguration von {} kopiert, noch nicht ins Gerät geschrieben"),
+    ("{} was saved from a {} device and can't be applied to {}","{} wurde von einem {}-Gerät gespeichert und kann nicht auf {} angewendet werden"),
+    ("{} was saved from {} and converted for {}.\n{}","{} wurde von {} gespeichert und für {} umgewandelt.\n{}"),
+    ("Configuration migrated","Konfiguration übernommen"),
+    ("The previous configuration was carried over to the new firmware.\n{}\nReview it and press 'Write configuration' to store it.",
+     "Die vorherige Konfiguration wurde in die neue Firmware übernommen.\n{}\nPrüfen und 'Konfiguration schreiben' drücken, um sie zu speichern."),
+    ("Migrated configuration not yet written","Übernommene Konfiguration noch nicht geschrieben"),
+    ("Read configuration","Konfiguration lesen"),
+    ("Write configuration","Konfiguration schreiben"),
+    ("Reading config...","Lese Konfiguration..."),
+    ("Writing configuration...","Schreibe Konfiguration..."),
+    ("Config read from the device","Konfiguration vom Gerät gelesen"),
+    ("Config written to the device","Konfiguration ins Gerät geschrieben"),
+    ("Failed to read configuration from device","Konfiguration konnte nicht vom Gerät gelesen werden"),
+    ("¡¡¡ Error writing the configuration","¡¡¡ Fehler beim Schreiben der Konfiguration"),
+    ("⚠ The firmware differs from the spec: {}","⚠ Die Firmware weicht von der Spezifikation ab: {}"),
+    ("Spec of {} compared with the last read from the device.\n\n{}","Spezifikation von {} verglichen mit dem letzten Lesen vom Gerät.\n\n{}"),
+    ("Spec drift","Abweichungen von der Spezifikation"),
+    ("Drift report","Abweichungsbericht"),
+    ("{} field(s) not in the spec","{} Feld(er) nicht in der Spezifikation"),
+    ("{} spec field(s) not reported","{} Feld(er) der Spezifikation nicht gemeldet"),
+    ("{} caption(s) differ","{} Bezeichnung(en) abweichend"),
+    ("{} value(s) out of the spec limits","{} Wert(e) außerhalb der Grenzen der Spezifikation"),
+    ("{} limit(s) differ","{} Grenze(n) abweichend"),
+    ("The firmware matches the spec.","Die Firmware entspricht der Spezifikation."),
+    ("Reported by the device but missing from the spec (not editable):","Vom Gerät gemeldet, aber nicht in der Spezifikation (nicht bearbeitbar):"),
+    ("In the spec but not reported by the device (shown with defaults):","In der Spezifikation, aber nicht vom Gerät gemeldet (mit Standardwerten angezeigt):"),
+    ("Captions that differ (spec / firmware):","Abweichende Bezeichnungen (Spezifikation / Firmware):"),
+    ("Values the spec does not accept (range or format changed?):","Werte, die die Spezifikation nicht zulässt (Bereich oder Format geändert?):"),
+    ("Limits that differ (spec / firmware):","Abweichende Grenzen (Spezifikation / Firmware):"),
+    ("up to {} bytes","bis {} Bytes"),
+    ("up to {} characters","bis {} Zeichen"),
+    ("choices {}","Auswahl {}"),
+    ("No changes were needed.","Es waren keine Änderungen nötig."),
+    ("Converted: {}","Umgewandelt: {}"),
+    ("Dropped: {}","Verworfen: {}"),
+    ("{} (not in spec)","{} (nicht in der Spezifikation)"),
+    ("'{}' is renamed to '{}'","'{}' wird in '{}' umbenannt"),
+    ("'{}' is removed","'{}' entfällt"),
+    ("'{}' values change ({})","Werte von '{}' ändern sich ({})"),
+    ("'{}' is rescaled (x{} {})","'{}' wird umgerechnet (x{} {})"),
+    ("'{}' is set to {}","'{}' wird auf {} gesetzt"),
+    ("{} (generic editor)","{} (generischer Editor)"),
+    ("{} (described by the firmware)","{} (von der Firmware beschrieben)"),
+    ("Other","Sonstige"),
+    ("Parameters","Parameter"),
+    ("Parameters reported by the device but not described by its spec. They are edited as plain text.",
+     "Vom Gerät gemeldete Parameter, die seine Spezifikation nicht beschreibt. Sie werden als Text bearbeitet."),
+    ("This device is not in the spec. Every parameter is edited as text,\ncheck the firmware documentation for valid values.",
+     "Dieses Gerät ist nicht in der Spezifikation. Alle Parameter werden als Text bearbeitet,\ngültige Werte stehen in der Dokumentation der Firmware."),
+    ("Devices","Geräte"),
+
+    // Compare, profiles and history
+    ("Compare","Vergleichen"),
+    ("Compare {}","{} vergleichen"),
+    ("Reading config to compare...","Lese Konfiguration zum Vergleichen..."),
+    ("Can't read the device configuration: {}","Die Gerätekonfiguration kann nicht gelesen werden: {}"),
+    ("Field","Feld"),
+    ("Device","Gerät"),
+    ("Editor","Editor"),
+    ("Use","Verwenden"),
+    ("Apply to editor","In den Editor übernehmen"),
+    ("{} field(s) differ between the device and the editor (≠). '*' marks values that differ from the spec default.",
+     "{} Feld(er) unterscheiden sich zwischen Gerät und Editor (≠). '*' markiert Werte, die vom Standardwert abweichen."),
+    ("{} field(s) updated from the comparison, not yet written","{} Feld(er) aus dem Vergleich übernommen, noch nicht geschrieben"),
+    ("Profiles","Profile"),
+    ("Profiles for {}","Profile für {}"),
+    ("Profile '{}'","Profil '{}'"),
+    ("Profile '{}' applied ({} fields), not yet written","Profil '{}' angewendet ({} Felder), noch nicht geschrieben"),
+    ("Profile '{}' saved with {} fields","Profil '{}' mit {} Feldern gespeichert"),
+    ("Profile '{}' deleted","Profil '{}' gelöscht"),
+    ("Can't load profile '{}': {}","Profil '{}' kann nicht geladen werden: {}"),
+    ("Can't save profile '{}': {}","Profil '{}' kann nicht gespeichert werden: {}"),
+    ("Can't delete profile '{}': {}","Profil '{}' kann nicht gelöscht werden: {}"),
+    ("Apply","Anwenden"),
+    ("Delete","Löschen"),
+    ("Save current as","Aktuelle speichern als"),
+    ("Delete the profile '{}'?","Profil '{}' löschen?"),
+    ("Type a name for the new profile","Einen Namen für das neue Profil eingeben"),
+    ("New profiles keep the fields that differ from the defaults, except the Device ID.","Neue Profile enthalten die Felder, die von den Standardwerten abweichen, außer der Geräte-ID."),
+    ("History","Verlauf"),
+    ("History of {}","Verlauf von {}"),
+    ("No configuration of this device has been stored yet.","Von diesem Gerät wurde noch keine Konfiguration gespeichert."),
+    ("The snapshot of {}","Der Stand vom {}"),
+    ("No differences","Keine Unterschiede"),
+    ("From","Von"),
+    ("To","Bis"),
+    ("Restore 'To' snapshot","Stand 'Bis' wiederherstellen"),
+    ("Restoring snapshot...","Stelle Stand wieder her..."),
+
+    // Device information and firmware
+    ("Device info","Geräteinfo"),
+    ("Device information - {}","Geräteinformationen - {}"),
+    ("Copy to clipboard","In die Zwischenablage kopieren"),
+    ("Clipboard","Zwischenablage"),
+    ("{}.\nSelect the text and copy it by hand.","{}.\nDen Text markieren und von Hand kopieren."),
+    ("What's new","Neuigkeiten"),
+    ("Firmware updates","Firmware-Aktualisierungen"),
+    ("This firmware needs boros_gui {} or newer.","Diese Firmware benötigt boros_gui {} oder neuer."),
+    ("Firmware V{} is available (device has V{}).","Firmware V{} ist verfügbar (das Gerät hat V{})."),
+    ("Firmware","Firmware"),
+    ("Flash firmware","Firmware flashen"),
+    ("Flashing firmware, do not unplug the device...","Flashe Firmware, Gerät nicht abstecken..."),
+    ("Firmware not flashed","Firmware nicht geflasht"),
+    ("Firmware written but the device did not answer after reset","Firmware geschrieben, aber das Gerät hat nach dem Neustart nicht geantwortet"),
+    ("Flashing failed: {}","Flashen fehlgeschlagen: {}"),
+    ("Writing firmware, page {} of {}","Schreibe Firmware, Seite {} von {}"),
+    ("Verifying firmware, page {} of {}","Prüfe Firmware, Seite {} von {}"),
+    ("Firmware flashed and verified, device is now {}","Firmware geflasht und geprüft, das Gerät ist jetzt {}"),
+    ("The new firmware reports {}, which is not in the spec","Die neue Firmware meldet {}, das nicht in der Spezifikation ist"),
+    ("Unknown device signature after flashing","Unbekannte Gerätesignatur nach dem Flashen"),
+    ("¡¡¡ Firmware update failed","¡¡¡ Firmware-Aktualisierung fehlgeschlagen"),
+
+    // Preferences
+    ("Preferences","Einstellungen"),
+    ("General","Allgemein"),
+    ("Serial","Seriell"),
+    ("Advanced","Erweitert"),
+    ("Cancel","Abbrechen"),
+    ("Default port","Standardport"),
+    ("Connect to the default port at startup","Beim Start mit dem Standardport verbinden"),
+    ("Reconnect when an unplugged device returns","Neu verbinden, wenn ein abgestecktes Gerät zurückkommt"),
+    ("Write a protocol transcript","Protokollmitschnitt schreiben"),
+    ("Transcript file","Mitschnittdatei"),
+    ("Language (from the next start)","Sprache (ab dem nächsten Start)"),
+    ("System language","Systemsprache"),
+    ("Spec files, one per line, used from the next start.\nThe built-in spec is used if none can be read.",
+     "Spezifikationsdateien, eine pro Zeile, ab dem nächsten Start verwendet.\nIst keine lesbar, wird die eingebaute Spezifikation verwendet."),
+    ("Empty fields use the values of the spec.","Leere Felder verwenden die Werte der Spezifikation."),
+    ("Baud rate","Baudrate"),
+    ("Data bits","Datenbits"),
+    ("Parity","Parität"),
+    ("Stop bits","Stoppbits"),
+    ("Flow control","Flusskontrolle"),
+    ("Reset line","Reset-Leitung"),
+    ("Reset pulse (ms)","Reset-Impuls (ms)"),
+    ("Boot delay (ms)","Startverzögerung (ms)"),
+    ("Bootloader baud rate","Baudrate des Bootloaders"),
+    ("Read timeout (ms)","Lese-Timeout (ms)"),
+    ("Pause before a command (ms)","Pause vor einem Befehl (ms)"),
+    ("Wait for the answer (ms)","Warten auf die Antwort (ms)"),
+    ("Port window width","Breite des Portfensters"),
+    ("Port window height","Höhe des Portfensters"),
+    ("Editor width","Breite des Editors"),
+    ("Editor height","Höhe des Editors"),
+    ("Window sizes apply to windows opened from the next start.","Fenstergrößen gelten für Fenster ab dem nächsten Start."),
+];
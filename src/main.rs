@@ -1,92 +1,365 @@
-use serialport::{available_ports,open,SerialPortType,UsbPortInfo};
 use std::sync::mpsc::channel;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::cell::RefCell;
 
 extern crate iui;
 use iui::prelude::*;
-use iui::controls::{Label, Button, VerticalBox, HorizontalBox,Combobox,ProgressBar};
+use iui::controls::{Label, Button, VerticalBox, HorizontalBox,ProgressBar,Checkbox};
+use iui::menus::Menu;
 
-use ser::BorosSerial;
+use boros_core::ser::{BorosSerial,ConnectMode,CmdStats};
+use boros_core::spec::{Root,Device,DeviceInfo,ConfigEntry};
+use boros_core::transcript::Transcript;
+use boros_core::detect::{self,Detected,UsbFilter};
+use boros_core::settings::{SerialOverride,SerialSettings,Timeouts};
+use boros_core::profiles::Profiles;
+use boros_core::snapshot::ConfigFile;
+use boros_core::{replay,lang};
 use editor::Editor;
+use cli::{Options,ProfileCmd,USAGE};
+use ports::PortList;
+use devlist::DeviceList;
+use prefs::Prefs;
+use i18n::{tr,trf};
 
-mod ser;
 mod editor;
-mod devices;
+mod cli;
+mod ports;
+mod devlist;
+mod dialogs;
+mod prefs;
+mod clipboard;
+mod i18n;
+mod reports;
+
+// A device that answered on a port, ready to be edited
+pub struct Opened {
+    pub ser: Box<BorosSerial>,
+    pub device: Device,
+    pub info: DeviceInfo,
+}
 
 pub enum Actions {
-    OpenEditor(String),
+    OpenEditor(String,ConnectMode),
+    // Port, then the connected device and its spec index or the error to show
+    Connected(String,Result<Opened,String>),
+    ShowEditor(usize),
+    ShowDevices,
+    CloseEditor(usize),
+    // Editor id asking to connect its port again, then the outcome
+    Reconnect(usize),
+    Reconnected(usize,Result<Opened,String>),
+    ToggleLink,
+    // I/O failure reported by a worker job of an editor
+    LinkLost(String),
+    CopyConfig(usize,usize),
     PBShow,
     PBHide,
     EditorInfo(String),
     Reset,
+    // Config read as the backup of a factory reset, then the `fac` outcome
+    BackupRead(Result<Vec<(String,String)>,String>),
+    FactoryDone(bool),
+    RestoreBackup,
+    ShowHistory,
+    ShowProfiles,
+    ApplyProfile(String),
+    SaveProfile(String),
+    DeleteProfile(String),
+    Compare,
+    CompareRead(Result<Vec<(String,String)>,String>),
+    ApplyValues(Vec<(String,String)>),
+    RestoreSnapshot(PathBuf),
+    DefaultItem(String),
+    DefaultSection(usize),
+    DefaultAll,
     ReadConfig,
+    ShowDrift,
+    ShowDeviceInfo,
+    // Command statistics of the worker, sent after each job
+    Stats(CmdStats),
+    // Firmware description of a device in the spec, if it has one
+    FirmwareSpec(Option<Device>),
+    ConfigRead(Result<Vec<ConfigEntry>,String>),
     SaveConfig,
+    ConfigWritten(Result<Vec<(String,String)>,String>),
+    Transcript(Option<PathBuf>),
+    Detected(Vec<Detected>),
+    Flash(PathBuf),
+    // Device after flashing, with its description when the spec lacks it
+    Flashed(Result<(DeviceInfo,Option<Device>),String>),
+    SaveFile(PathBuf),
+    LoadFile(PathBuf),
+    Prefs(Box<Prefs>),
+}
+
+// Spec of the first usable file in `specs`, else the built-in one
+fn load_spec(specs: &[PathBuf]) -> Option<Root> {
+    match Root::load(specs) {
+        Ok((root,skipped)) => {
+            for s in skipped { eprintln!("Ignoring spec {}",s) }
+            Some(root)
+        }
+        Err(e) => { eprintln!("Can't load spec: {}",e); None }
+    }
+}
+
+fn run_replays(files: &[PathBuf], specs: &[PathBuf]) -> i32 {
+    let root=match load_spec(specs) {
+        Some(r) => r,
+        None => return 2,
+    };
+    // A recording answers at once, the protocol delays would only slow it down
+    let timing=Timeouts { command_gap_ms: 0, answer_ms: 0, ..Timeouts::default() };
+    let mut status=0;
+    for f in files {
+        match replay::check(f,&root,timing) {
+            Ok(r) => {
+                println!("PASS {}: {}",f.display(),r.report.join(", "));
+                if let Some(d)=r.drift {
+                    println!("  {}",trf("⚠ The firmware differs from the spec: {}",&[&reports::drift_summary(&d)]));
+                }
+            },
+            Err(errors) => {
+                println!("FAIL {}",f.display());
+                for e in errors { println!("  {}",e) }
+                status=1;
+            }
+        }
+    }
+    status
+}
+
+// Checks saved configuration files against the spec without a device
+fn run_validate(files: &[PathBuf], specs: &[PathBuf]) -> i32 {
+    let root=match load_spec(specs) {
+        Some(r) => r,
+        None => return 2,
+    };
+    let mut status=0;
+    for f in files {
+        let config=match ConfigFile::load(f) {
+            Ok(c) => c,
+            Err(e) => { println!("FAIL {}: {}",f.display(),e); status=1; continue }
+        };
+        let mut dev=match root.device(&config.signature) {
+            Some(d) => d.clone(),
+            None => { println!("FAIL {}: no spec for {}",f.display(),config.signature); status=1; continue }
+        };
+        let rejected=dev.apply(&config.values());
+        if rejected.is_empty() {
+            println!("PASS {}: {} item(s) valid for {}",f.display(),config.values.len(),dev.signature);
+        } else {
+            println!("FAIL {}",f.display());
+            for e in rejected { println!("  {}",e) }
+            status=1;
+        }
+    }
+    status
+}
+
+fn connect_mode(attach: bool) -> ConnectMode {
+    if attach { ConnectMode::Attach } else { ConnectMode::Reset }
+}
+
+fn run_detect(filter: &UsbFilter, ov: &SerialOverride, mode: ConnectMode, specs: &[PathBuf], all: bool) -> i32 {
+    let root=match load_spec(specs) {
+        Some(r) => r,
+        None => return 2,
+    };
+    let found: Vec<Detected>=detect::detect(filter,&root.candidate_settings(ov),mode).into_iter().filter( |d| all || root.find(&d.signature).is_some() ).collect();
+    for d in &found {
+        let mark=if root.find(&d.signature).is_some() { "" } else { "\tnot in spec" };
+        println!("{}\t{}{}",d.port,d.signature,mark);
+    }
+    if found.is_empty() { 1 } else { 0 }
+}
+
+fn run_profiles(cmd: &ProfileCmd) -> std::io::Result<()> {
+    let lib=Profiles::open();
+    match cmd {
+        ProfileCmd::List => {
+            for p in lib.list() {
+                println!("{}\t{}\t{} item(s)",p.name,p.config.signature,p.config.values.len());
+            }
+        },
+        ProfileCmd::Show(name) => print!("{}",lib.load(name)?),
+        ProfileCmd::Delete(name) => lib.remove(name)?,
+        ProfileCmd::Import(path) => {
+            let p=lib.import(path)?;
+            println!("Imported profile '{}' for {}",p.name,p.config.signature);
+        },
+    }
+    Ok(())
+}
+
+fn open_transcript(path: &Option<PathBuf>) -> Option<Transcript> {
+    path.as_ref().and_then(|p| Transcript::create(p).map_err(|e| eprintln!("Can't open transcript {}: {}",p.display(),e)).ok())
+}
+
+// Opens `port` with each line setting until a device answers. Devices missing
+// from the spec describe themselves or get a generic editor. Runs outside the
+// GUI thread.
+fn connect(port: &str,lines: &[SerialSettings],mode: ConnectMode,transcript: &Option<PathBuf>,timing: Timeouts,root: &Root) -> Result<Opened,String> {
+    let mut opened=false;
+    let mut unknown=None;
+    for line in lines {
+        if let Ok(p)=line.open(port) {
+            opened=true;
+            let mut ser=BorosSerial::with_settings(Box::new(p),*line);
+            ser.set_transcript(open_transcript(transcript));
+            ser.set_timeouts(timing);
+            if !ser.open(mode) {
+                continue;
+            }
+            if let Ok(info)=ser.get_info() {
+                if let Some(dev)=root.device(&info.signature) {
+                    return Ok(Opened { ser: Box::new(ser), device: dev.clone(), info });
+                }
+                match ser.describe_device(&info.signature) {
+                    Ok(device) => return Ok(Opened { ser: Box::new(ser), device, info }),
+                    Err(e) => unknown=Some(trf("{} is not in the spec and its configuration can't be read: {}",&[&info.signature,&e])),
+                }
+            }
+        }
+    }
+    if let Some(msg)=unknown {
+        Err(msg)
+    } else if !opened {
+        Err(tr("Couldn't open the serial interface").into())
+    } else if mode == ConnectMode::Attach {
+        Err(tr("The device did not answer with a prompt. Uncheck 'Attach without reset' to reboot it and retry.").into())
+    } else {
+        Err(tr("Couldn't retrieve a valid signature of the device").into())
+    }
 }
 
 
 fn main() {
-    
+    let opts = Options::parse().unwrap_or_else(|e| {
+        eprintln!("{}\n{}",e,USAGE);
+        std::process::exit(2);
+    });
+    let prefs=Prefs::load();
+    lang::select(prefs.language.as_deref());
+    let ov=prefs.serial.or(&opts.serial);
+    let mode=opts.mode.unwrap_or(connect_mode(prefs.attach));
+    if !opts.replay.is_empty() {
+        std::process::exit(run_replays(&opts.replay,&prefs.spec_paths));
+    }
+    if !opts.validate.is_empty() {
+        std::process::exit(run_validate(&opts.validate,&prefs.spec_paths));
+    }
+    if let Some(cmd)=&opts.profile_cmd {
+        if let Err(e)=run_profiles(cmd) {
+            eprintln!("{}",e);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+    if opts.detect {
+        std::process::exit(run_detect(&opts.usb_filter,&ov,mode,&prefs.spec_paths,opts.detect_all));
+    }
+    let root=match load_spec(&prefs.spec_paths) {
+        Some(r) => r,
+        None => std::process::exit(2),
+    };
 
     let ui : UI = UI::init().unwrap();
     let (cmd_sender,cmd_receiver) = channel::<Actions>();
-    let mut editor : Editor = Editor::new(ui.clone(),cmd_sender.clone()).unwrap();
-    
-    let mut w_select = Window::new(&ui, "Choose serial", 320, 200, WindowType::NoMenubar);
-
-     // Layout & group for select window
-     let mut vbox = VerticalBox::new(&ui);
-     vbox.set_padded(&ui, true);
-     let mut device_combo= Combobox::new(&ui);
-     let ports=available_ports().unwrap();
-     
-     for i in ports.iter() {
-         device_combo.append(&ui,&i.port_name);
-     }
-    
-    let info= Label::new(&ui,"Select serial interface");
-    let mut progress = ProgressBar::indeterminate(&ui);
-    progress.hide(&ui);
 
-    device_combo.on_selected(&ui, {
+    let transcript=opts.transcript.clone().or_else( || prefs.transcript() );
+    let shared_prefs=Rc::new(RefCell::new(prefs.clone()));
+
+    // Menus must exist before any window is created
+    let tools_menu = Menu::new(&ui,tr("Tools"));
+    let mut prefs_item = tools_menu.append_item(tr("Preferences..."));
+    prefs_item.on_clicked(&ui, {
         let ui=ui.clone();
-        let mut inf=info.clone();
-        let p=ports.clone();
-        move |i| {
-            if i>=0 {
-                let s:String=match &p[i as usize].port_type {
-                    SerialPortType::BluetoothPort => "Bluetooth interface".into(),
-                    SerialPortType::PciPort => "Pci interface".into(),
-                    SerialPortType::Unknown => "Unknown interface ".into(),
-                    SerialPortType::UsbPort(UsbPortInfo { manufacturer, product , ..}) => {
-                        format!("USB Vendor:{},Product:{}",manufacturer.as_ref().unwrap_or(&"#".to_string()),product.as_ref().unwrap_or(&"#".to_string()))
-                    }
-                };
-                inf.set_text(&ui,&s);
+        let c=cmd_sender.clone();
+        let p=shared_prefs.clone();
+        move |_,_| prefs::show_dialog(&ui,&p.borrow(),c.clone())
+    });
+    let mut log_item = tools_menu.append_check_item(tr("Protocol transcript"));
+    log_item.set_checked(&ui,transcript.is_some());
+    log_item.on_clicked(&ui, {
+        let ui=ui.clone();
+        let c=cmd_sender.clone();
+        move |item,win| {
+            if item.is_checked(&ui) {
+                if let Some(p)=win.save_file(&ui) {
+                    let _=c.send(Actions::Transcript(Some(p)));
+                } else {
+                    item.set_checked(&ui,false);
+                }
+            } else {
+                let _=c.send(Actions::Transcript(None));
             }
         }
     });
-    if ports.len() > 0 {
-        device_combo.set_selected(&ui,1); // Todo change to 0
+
+    let mut editors: Vec<Editor>=vec!();
+    let mut next_id=0;
+
+    let lines = Rc::new(RefCell::new(root.candidate_settings(&ov)));
+
+    let (sw,sh)=prefs.select_size;
+    let mut w_select = Window::new(&ui, tr("Boros devices"), sw, sh, WindowType::HasMenubar);
+
+    // Layout & group for select window
+    let mut vbox = VerticalBox::new(&ui);
+    vbox.set_padded(&ui, true);
+    let info= Label::new(&ui,tr("Select a serial interface"));
+    let port_list = Rc::new(RefCell::new(PortList::new(&ui,info.clone())));
+    if let Some(p)=&prefs.port {
+        port_list.borrow_mut().select(p);
     }
+    let mut attach_check = Checkbox::new(&ui,tr("Attach without reset"));
+    attach_check.set_checked(&ui,mode == ConnectMode::Attach);
+    let mut device_list=DeviceList::new(&ui,cmd_sender.clone());
+    let mut progress = ProgressBar::indeterminate(&ui);
+    progress.hide(&ui);
 
     let mut group_hbox = HorizontalBox::new(&ui);
-    let mut but_go = Button::new(&ui,"Go!");
+    let mut but_detect = Button::new(&ui,tr("Auto-detect"));
+    but_detect.on_clicked(&ui, {
+        let c=cmd_sender.clone();
+        let ui=ui.clone();
+        let filter=opts.usb_filter;
+        let lines=lines.clone();
+        let ac=attach_check.clone();
+        move |_| {
+            let _=c.send(Actions::PBShow);
+            let c=c.clone();
+            let lines=lines.borrow().clone();
+            let mode=connect_mode(ac.checked(&ui));
+            std::thread::spawn(move || {
+                let _=c.send(Actions::Detected(detect::detect(&filter,&lines,mode)));
+            });
+        }
+    });
+
+    let mut but_go = Button::new(&ui,tr("Open"));
     but_go.on_clicked(&ui, {
         let ui = ui.clone();
-        let dc=device_combo.clone();
+        let cmd_sender=cmd_sender.clone();
+        let pl=port_list.clone();
         let w=w_select.clone();
+        let ac=attach_check.clone();
 
         move |_| {
-            let n=dc.selected(&ui);
-            if n == -1 {
-                w.modal_err(&ui,"Error","No serial interface selected");
-            } else {
-                let _= cmd_sender.send(Actions::PBShow);
-                let _= cmd_sender.send(Actions::OpenEditor(ports[n as usize].port_name.clone()));
+            match pl.borrow().selected() {
+                None => w.modal_err(&ui,tr("Error"),tr("No serial interface selected")),
+                Some(port) => {
+                    let _= cmd_sender.send(Actions::PBShow);
+                    let _= cmd_sender.send(Actions::OpenEditor(port,connect_mode(ac.checked(&ui))));
+                }
             }
         }
     });
 
-    let mut quit_button = Button::new(&ui, "Quit");
+    let mut quit_button = Button::new(&ui, tr("Quit"));
     quit_button.on_clicked(&ui, {
         let ui = ui.clone();
         move |_| {
@@ -94,50 +367,182 @@ fn main() {
         }
     });
 
-    vbox.append(&ui, device_combo, LayoutStrategy::Stretchy);
-    vbox.append(&ui, info, LayoutStrategy::Stretchy);
+    vbox.append(&ui, port_list.borrow().group(), LayoutStrategy::Stretchy);
+    vbox.append(&ui, info.clone(), LayoutStrategy::Stretchy);
+    vbox.append(&ui, attach_check.clone(), LayoutStrategy::Compact);
     vbox.append(&ui, progress.clone(), LayoutStrategy::Stretchy);
     group_hbox.append(&ui, quit_button, LayoutStrategy::Stretchy);
+    group_hbox.append(&ui, but_detect, LayoutStrategy::Stretchy);
     group_hbox.append(&ui, but_go, LayoutStrategy::Stretchy);
     //group.set_child(&ui, group_hbox);
     vbox.append(&ui, group_hbox, LayoutStrategy::Stretchy);
+    vbox.append(&ui, device_list.group(), LayoutStrategy::Compact);
     //vbox.append(&ui, bar, LayoutStrategy::Stretchy);
 
     // Show the window
     w_select.set_child(&ui, vbox);
     w_select.show(&ui);
+    if let (Some(p),true)=(&prefs.port,prefs.auto_connect) {
+        let _=cmd_sender.send(Actions::PBShow);
+        let _=cmd_sender.send(Actions::OpenEditor(p.clone(),mode));
+    }
     // Run the application
     let mut event_loop = ui.event_loop();
     event_loop.on_tick(&ui, {
         let mut w=w_select.clone();
         let ui=ui.clone();
         let mut pb=progress.clone();
+        let pl=port_list.clone();
+        let mut inf=info.clone();
+        let mut transcript_path=transcript;
+        let shared_prefs=shared_prefs.clone();
+        let cli=opts;
+        let cmd_sender=cmd_sender.clone();
         move || {
+            pl.borrow_mut().tick();
+            let mut list_changed=false;
             if let Ok(msg)=cmd_receiver.try_recv() {
                 match msg {
-                    Actions::OpenEditor(dev) => {
-                        if let Ok(ser)=open(&dev) {
-                            editor.take_serial(Box::new(BorosSerial::new(ser)));
-                            let n =editor.get_and_check_signature();
-                            if n< usize::MAX {
-                                editor.show(n);
-                                w.hide(&ui);
-                            } else {
-                                w.modal_err(&ui,"Error","Couldn't not retrieve a valid signature of the device");
-                            }
+                    Actions::OpenEditor(dev,mode) => {
+                        // A port already open just brings its editor back
+                        if let Some(e)=editors.iter_mut().find( |e| e.port() == dev ) {
+                            e.present();
+                            pb.hide(&ui);
                         } else {
-                            w.modal_err(&ui,"Error","Couldn't open serial interface");
+                            let c=cmd_sender.clone();
+                            let lines=lines.borrow().clone();
+                            let transcript=transcript_path.clone();
+                            let timing=shared_prefs.borrow().timeouts;
+                            let root=root.clone();
+                            std::thread::spawn(move || {
+                                let res=connect(&dev,&lines,mode,&transcript,timing,&root);
+                                let _=c.send(Actions::Connected(dev,res));
+                            });
                         }
+                    },
+                    Actions::Connected(dev,res) => {
+                        match res {
+                            Ok(opened) => {
+                                // Each editor edits its own copy, with the device added if it described itself
+                                let mut r=root.clone();
+                                let n=r.add(opened.device);
+                                let mut e=Editor::new(ui.clone(),cmd_sender.clone(),next_id,&dev,mode,r,&shared_prefs.borrow());
+                                next_id+=1;
+                                e.start_profile(cli.profile.clone());
+                                e.take_serial(*opened.ser);
+                                e.set_device_info(opened.info);
+                                e.show(n);
+                                editors.push(e);
+                                let mut p=shared_prefs.borrow_mut();
+                                if p.port.as_ref() != Some(&dev) {
+                                    p.port=Some(dev);
+                                    let _=p.save();
+                                }
+                            },
+                            Err(msg) => w.modal_err(&ui,tr("Error"),&msg),
+                        }
+                        list_changed=true;
                         pb.hide(&ui);
                     },
+                    Actions::Reconnect(id) => {
+                        if let Some(e)=editors.iter().find( |e| e.id() == id ) {
+                            let (port,mode)=e.connection();
+                            let c=cmd_sender.clone();
+                            let lines=lines.borrow().clone();
+                            let transcript=transcript_path.clone();
+                            let timing=shared_prefs.borrow().timeouts;
+                            let root=root.clone();
+                            std::thread::spawn(move || {
+                                let _=c.send(Actions::Reconnected(id,connect(&port,&lines,mode,&transcript,timing,&root)));
+                            });
+                        }
+                    },
+                    Actions::Reconnected(id,res) => {
+                        if let Some(e)=editors.iter_mut().find( |e| e.id() == id ) {
+                            e.reconnected(res);
+                            list_changed=true;
+                        }
+                    },
+                    Actions::ShowDevices => w.show(&ui),
+                    Actions::ShowEditor(id) => {
+                        if let Some(e)=editors.iter_mut().find( |e| e.id() == id ) {
+                            e.present();
+                        }
+                    },
+                    Actions::CloseEditor(id) => {
+                        if let Some(n)=editors.iter().position( |e| e.id() == id ) {
+                            editors.remove(n).close();
+                            list_changed=true;
+                        }
+                    },
+                    Actions::CopyConfig(from,to) => {
+                        let a=editors.iter().position( |e| e.id() == from );
+                        let b=editors.iter().position( |e| e.id() == to );
+                        match (a,b) {
+                            (Some(a),Some(b)) if a != b => {
+                                match editors[a].shared_config() {
+                                    Ok(f) => {
+                                        let origin=editors[a].describe();
+                                        editors[b].copy_from(&f,&origin);
+                                    },
+                                    Err(msg) => w.modal_err(&ui,tr("Copy configuration"),&msg),
+                                }
+                            },
+                            _ => w.modal_err(&ui,tr("Copy configuration"),tr("Select two different open devices")),
+                        }
+                    },
                     Actions::PBHide => pb.hide(&ui),
                     Actions::PBShow => pb.show(&ui),
-                    Actions::EditorInfo(s) => editor.editor_info(&s),
-                    Actions::Reset  => editor.reset(),
-                    Actions::ReadConfig => editor.read_config(),
-                    Actions::SaveConfig => editor.save_config(),
-                    //_ => {}
+                    Actions::Detected(found) => {
+                        // Devices missing from the spec can still be opened with a generic editor
+                        let mut known: Vec<&Detected>=found.iter().collect();
+                        known.sort_by_key( |d| root.find(&d.signature).is_none() );
+                        if let Some(first)=known.first() {
+                            pl.borrow_mut().select(&first.port);
+                            let l: Vec<String>=known.iter().map( |d| {
+                                let mark=if root.find(&d.signature).is_some() { "" } else { tr(", not in spec") };
+                                format!("{} ({}{})",d.port,d.signature,mark)
+                            }).collect();
+                            inf.set_text(&ui,&trf("Found: {}",&[&l.join(", ")]));
+                        } else {
+                            inf.set_text(&ui,tr("No Boros device found"));
+                        }
+                        pb.hide(&ui);
+                    },
+                    Actions::Transcript(p) => {
+                        transcript_path=p;
+                        for e in editors.iter_mut() {
+                            e.set_transcript(open_transcript(&transcript_path));
+                        }
+                    },
+                    Actions::Prefs(p) => {
+                        for e in editors.iter_mut() {
+                            e.set_prefs(&p);
+                        }
+                        *lines.borrow_mut()=root.candidate_settings(&p.serial.or(&cli.serial));
+                        if cli.transcript.is_none() && transcript_path != p.transcript() {
+                            transcript_path=p.transcript();
+                            for e in editors.iter_mut() {
+                                e.set_transcript(open_transcript(&transcript_path));
+                            }
+                        }
+                        *shared_prefs.borrow_mut() = *p;
+                    },
+                    // Editors handle their own actions in tick()
+                    _ => {}
+                }
+            }
+            for e in editors.iter_mut() {
+                if e.check_link(pl.borrow().has(e.port())) {
+                    list_changed=true;
                 }
+                if e.tick() {
+                    list_changed=true;
+                }
+            }
+            if list_changed {
+                let entries: Vec<(usize,String)>=editors.iter().map( |e| (e.id(),e.describe()) ).collect();
+                device_list.update(&entries);
             }
         }
     });
@@ -0,0 +1,143 @@
+use serialport::{available_ports,SerialPortInfo,SerialPortType,UsbPortInfo};
+use std::time::{Duration,Instant};
+
+use iui::prelude::*;
+use iui::controls::{Combobox,Group,Label};
+
+use crate::i18n::tr;
+
+const RESCAN_PERIOD: Duration = Duration::from_secs(2);
+
+// Port chooser that follows hot-plug events. libui comboboxes can't be
+// cleared, so a fresh one is swapped into the group when the port set changes.
+pub struct PortList {
+    ui: UI,
+    group: Group,
+    combo: Combobox,
+    info: Label,
+    ports: Vec<SerialPortInfo>,
+    last_scan: Instant,
+}
+
+pub fn describe(p: &SerialPortInfo) -> String {
+    match &p.port_type {
+        SerialPortType::UsbPort(UsbPortInfo { manufacturer, product, serial_number, .. }) => {
+            let mut s=p.port_name.clone();
+            let name: Vec<&str>=[manufacturer,product].iter().filter_map( |x| x.as_deref() ).collect();
+            if !name.is_empty() { s.push_str(&format!(" - {}",name.join(" "))) }
+            if let Some(sn)=serial_number { s.push_str(&format!(" ({})",sn)) }
+            s
+        },
+        _ => p.port_name.clone(),
+    }
+}
+
+pub fn port_info(p: &SerialPortInfo) -> String {
+    match &p.port_type {
+        SerialPortType::BluetoothPort => "Bluetooth interface".into(),
+        SerialPortType::PciPort => "Pci interface".into(),
+        SerialPortType::Unknown => "Unknown interface ".into(),
+        SerialPortType::UsbPort(UsbPortInfo { vid, pid, manufacturer, product, serial_number }) => {
+            let na="#".to_string();
+            format!("USB {:04x}:{:04x} Vendor:{},Product:{},Serial:{}",vid,pid,
+                    manufacturer.as_ref().unwrap_or(&na),product.as_ref().unwrap_or(&na),serial_number.as_ref().unwrap_or(&na))
+        }
+    }
+}
+
+// Current description of a port, None once it is gone
+pub fn find(port: &str) -> Option<SerialPortInfo> {
+    available_ports().ok()?.into_iter().find( |p| p.port_name==port )
+}
+
+impl PortList {
+    pub fn new(ui: &UI, info: Label) -> Self {
+        let mut group=Group::new(ui,tr("Serial interface"));
+        let ports=available_ports().unwrap_or_default();
+        let combo=PortList::build(ui,&ports,info.clone());
+        group.set_child(ui,combo.clone());
+        let mut pl=Self {
+            ui: ui.clone(),
+            group,
+            combo,
+            info,
+            ports,
+            last_scan: Instant::now(),
+        };
+        if !pl.ports.is_empty() {
+            pl.set_selected(0);
+        }
+        pl
+    }
+
+    pub fn group(&self) -> Group {
+        self.group.clone()
+    }
+
+    fn build(ui: &UI, ports: &[SerialPortInfo], info: Label) -> Combobox {
+        let mut combo=Combobox::new(ui);
+        for p in ports {
+            combo.append(ui,&describe(p));
+        }
+        combo.on_selected(ui, {
+            let ui=ui.clone();
+            let mut inf=info;
+            let p=ports.to_vec();
+            move |i| {
+                if i>=0 {
+                    inf.set_text(&ui,&port_info(&p[i as usize]));
+                }
+            }
+        });
+        combo
+    }
+
+    fn set_selected(&mut self, n: usize) {
+        self.combo.set_selected(&self.ui,n as i32);
+        let s=port_info(&self.ports[n]);
+        self.info.set_text(&self.ui,&s);
+    }
+
+    pub fn selected(&self) -> Option<String> {
+        let n=self.combo.selected(&self.ui);
+        if n >= 0 { self.ports.get(n as usize).map( |p| p.port_name.clone() ) } else { None }
+    }
+
+    // False once the port was unplugged (as of the last scan)
+    pub fn has(&self, port: &str) -> bool {
+        self.ports.iter().any( |p| p.port_name==port )
+    }
+
+    pub fn select(&mut self, port: &str) {
+        if let Some(n)=self.ports.iter().position( |p| p.port_name==port ) {
+            self.set_selected(n);
+        }
+    }
+
+    // Called from the event loop; rescans at most every RESCAN_PERIOD
+    pub fn tick(&mut self) {
+        if self.last_scan.elapsed() >= RESCAN_PERIOD {
+            self.rescan();
+        }
+    }
+
+    pub fn rescan(&mut self) {
+        self.last_scan=Instant::now();
+        let ports=match available_ports() {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+        if ports == self.ports {
+            return;
+        }
+        let current=self.selected();
+        self.combo=PortList::build(&self.ui,&ports,self.info.clone());
+        self.group.set_child(&self.ui,self.combo.clone());
+        self.ports=ports;
+        match current.and_then( |c| self.ports.iter().position( |p| p.port_name==c ) ) {
+            Some(n) => self.set_selected(n),
+            None if !self.ports.is_empty() => self.set_selected(0),
+            None => self.info.set_text(&self.ui,tr("No serial interfaces")),
+        }
+    }
+}
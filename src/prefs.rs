@@ -0,0 +1,236 @@
+use serde::{Deserialize,Serialize};
+use std::io::{Result,Error,ErrorKind};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use iui::prelude::*;
+use iui::controls::{Label,Button,Entry,MultilineEntry,Checkbox,Spinbox,Combobox,
+                    VerticalBox,HorizontalBox,TabGroup,Spacer};
+
+use boros_core::settings::{SerialOverride,Timeouts,OVERRIDE_ARGS};
+use boros_core::paths::config_dir;
+use boros_core::lang::LANGUAGES;
+use crate::Actions;
+use crate::i18n::{tr,trf};
+
+// Settings remembered between runs, stored in <config dir>/prefs.yml.
+// Command line options take precedence over them.
+#[derive(Serialize,Deserialize,Clone)]
+#[serde(default)]
+pub struct Prefs {
+    pub port: Option<String>,
+    pub auto_connect: bool,
+    pub attach: bool,
+    // Connect again when a lost port returns with the same device
+    pub auto_reconnect: bool,
+    pub serial: SerialOverride,
+    pub timeouts: Timeouts,
+    // Spec files tried in order before the built-in one
+    pub spec_paths: Vec<PathBuf>,
+    pub log: bool,
+    pub log_file: Option<PathBuf>,
+    pub select_size: (i32,i32),
+    pub editor_size: (i32,i32),
+    // Code of the UI language, None to follow the locale
+    pub language: Option<String>,
+}
+
+impl Default for Prefs {
+    fn default() -> Self {
+        Self {
+            port: None,
+            auto_connect: false,
+            attach: false,
+            auto_reconnect: true,
+            serial: SerialOverride::default(),
+            timeouts: Timeouts::default(),
+            spec_paths: vec!(),
+            log: false,
+            log_file: None,
+            select_size: (320,200),
+            editor_size: (640,380),
+            language: None,
+        }
+    }
+}
+
+impl Prefs {
+    fn path() -> PathBuf {
+        config_dir().join("prefs.yml")
+    }
+
+    // Missing or unreadable preferences fall back to the defaults
+    pub fn load() -> Self {
+        match std::fs::read_to_string(Prefs::path()) {
+            Ok(text) => serde_yaml::from_str(&text).unwrap_or_else( |e| {
+                eprintln!("Ignoring {}: {}",Prefs::path().display(),e);
+                Prefs::default()
+            }),
+            Err(_) => Prefs::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(config_dir())?;
+        let text=serde_yaml::to_string(self).map_err( |e| Error::new(ErrorKind::InvalidData,e.to_string()) )?;
+        std::fs::write(Prefs::path(),text)
+    }
+
+    // Transcript file to open, if logging is enabled
+    pub fn transcript(&self) -> Option<PathBuf> {
+        if self.log { self.log_file.clone() } else { None }
+    }
+}
+
+fn row<T: Into<iui::controls::Control>>(ui: &UI, caption: &str, control: T) -> HorizontalBox {
+    let mut hb=HorizontalBox::new(ui);
+    hb.set_padded(ui,true);
+    hb.append(ui,Label::new(ui,caption),LayoutStrategy::Compact);
+    hb.append(ui,control,LayoutStrategy::Stretchy);
+    hb
+}
+
+fn spin(ui: &UI, min: i32, max: i32, v: i64) -> Spinbox {
+    let mut s=Spinbox::new(ui,min,max);
+    s.set_value(ui,v as i32);
+    s
+}
+
+// Edits a copy of `prefs`; on save the new values are stored and sent as Actions::Prefs
+pub fn show_dialog(ui: &UI, prefs: &Prefs, cmd: Sender<Actions>) {
+    let mut win=Window::new(ui,tr("Preferences"),420,360,WindowType::NoMenubar);
+    let mut tabs=TabGroup::new(ui);
+
+    // General
+    let mut port=Entry::new(ui);
+    port.set_value(ui,prefs.port.as_deref().unwrap_or(""));
+    let mut auto=Checkbox::new(ui,tr("Connect to the default port at startup"));
+    auto.set_checked(ui,prefs.auto_connect);
+    let mut attach=Checkbox::new(ui,tr("Attach without reset"));
+    attach.set_checked(ui,prefs.attach);
+    let mut reconnect=Checkbox::new(ui,tr("Reconnect when an unplugged device returns"));
+    reconnect.set_checked(ui,prefs.auto_reconnect);
+    let mut log=Checkbox::new(ui,tr("Write a protocol transcript"));
+    log.set_checked(ui,prefs.log);
+    let mut log_file=Entry::new(ui);
+    log_file.set_value(ui,&prefs.log_file.as_ref().map( |p| p.display().to_string() ).unwrap_or_default());
+    let mut language=Combobox::new(ui);
+    language.append(ui,tr("System language"));
+    for (_,name) in LANGUAGES {
+        language.append(ui,name);
+    }
+    let current=prefs.language.as_ref().and_then( |l| LANGUAGES.iter().position( |(c,_)| c==l ) );
+    language.set_selected(ui,current.map_or(0, |n| n as i32 + 1));
+    let mut specs=MultilineEntry::new(ui);
+    let paths: Vec<String>=prefs.spec_paths.iter().map( |p| p.display().to_string() ).collect();
+    specs.set_value(ui,&paths.join("\n"));
+    let mut general=VerticalBox::new(ui);
+    general.set_padded(ui,true);
+    general.append(ui,row(ui,tr("Default port"),port.clone()),LayoutStrategy::Compact);
+    general.append(ui,auto.clone(),LayoutStrategy::Compact);
+    general.append(ui,attach.clone(),LayoutStrategy::Compact);
+    general.append(ui,reconnect.clone(),LayoutStrategy::Compact);
+    general.append(ui,log.clone(),LayoutStrategy::Compact);
+    general.append(ui,row(ui,tr("Transcript file"),log_file.clone()),LayoutStrategy::Compact);
+    general.append(ui,row(ui,tr("Language (from the next start)"),language.clone()),LayoutStrategy::Compact);
+    general.append(ui,Label::new(ui,tr("Spec files, one per line, used from the next start.\nThe built-in spec is used if none can be read.")),LayoutStrategy::Compact);
+    general.append(ui,specs.clone(),LayoutStrategy::Stretchy);
+    tabs.append(ui,tr("General"),general);
+
+    // Serial line, empty fields keep the values of the spec
+    let mut serial=VerticalBox::new(ui);
+    serial.set_padded(ui,true);
+    let mut fields=vec!();
+    for (name,caption) in OVERRIDE_ARGS.iter() {
+        let mut e=Entry::new(ui);
+        e.set_value(ui,&prefs.serial.arg_value(name).unwrap_or_default());
+        serial.append(ui,row(ui,tr(caption),e.clone()),LayoutStrategy::Compact);
+        fields.push((*name,e));
+    }
+    serial.append(ui,Label::new(ui,tr("Empty fields use the values of the spec.")),LayoutStrategy::Compact);
+    tabs.append(ui,tr("Serial"),serial);
+
+    // Timing and windows
+    let read=spin(ui,100,60000,prefs.timeouts.read_ms as i64);
+    let gap=spin(ui,0,10000,prefs.timeouts.command_gap_ms as i64);
+    let answer=spin(ui,0,10000,prefs.timeouts.answer_ms as i64);
+    let sw=spin(ui,200,4000,prefs.select_size.0 as i64);
+    let sh=spin(ui,120,4000,prefs.select_size.1 as i64);
+    let ew=spin(ui,320,4000,prefs.editor_size.0 as i64);
+    let eh=spin(ui,200,4000,prefs.editor_size.1 as i64);
+    let mut timing=VerticalBox::new(ui);
+    timing.set_padded(ui,true);
+    timing.append(ui,row(ui,tr("Read timeout (ms)"),read.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Pause before a command (ms)"),gap.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Wait for the answer (ms)"),answer.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Port window width"),sw.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Port window height"),sh.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Editor width"),ew.clone()),LayoutStrategy::Compact);
+    timing.append(ui,row(ui,tr("Editor height"),eh.clone()),LayoutStrategy::Compact);
+    timing.append(ui,Label::new(ui,tr("Window sizes apply to windows opened from the next start.")),LayoutStrategy::Compact);
+    tabs.append(ui,tr("Advanced"),timing);
+
+    let mut save=Button::new(ui,tr("Save"));
+    let mut cancel=Button::new(ui,tr("Cancel"));
+    save.on_clicked(ui, {
+        let ui=ui.clone();
+        let mut w=win.clone();
+        let base=prefs.clone();
+        move |_| {
+            let mut p=base.clone();
+            let text=port.value(&ui);
+            p.port=if text.trim().is_empty() { None } else { Some(text.trim().to_string()) };
+            p.auto_connect=auto.checked(&ui);
+            p.attach=attach.checked(&ui);
+            p.auto_reconnect=reconnect.checked(&ui);
+            p.log=log.checked(&ui);
+            let text=log_file.value(&ui);
+            p.log_file=if text.trim().is_empty() { None } else { Some(PathBuf::from(text.trim())) };
+            let n=language.selected(&ui);
+            p.language=if n > 0 { LANGUAGES.get(n as usize - 1).map( |l| l.0.to_string() ) } else { None };
+            p.spec_paths=specs.value(&ui).lines().map(str::trim).filter( |l| !l.is_empty() ).map(PathBuf::from).collect();
+            let mut ov=SerialOverride::default();
+            for (name,e) in &fields {
+                let v=e.value(&ui);
+                if !v.trim().is_empty() {
+                    if let Err(msg)=ov.parse_arg(name,v.trim()) {
+                        w.modal_err(&ui,tr("Preferences"),&msg);
+                        return;
+                    }
+                }
+            }
+            p.serial=ov;
+            p.timeouts=Timeouts { read_ms: read.value(&ui) as u64, command_gap_ms: gap.value(&ui) as u64, answer_ms: answer.value(&ui) as u64 };
+            p.select_size=(sw.value(&ui),sh.value(&ui));
+            p.editor_size=(ew.value(&ui),eh.value(&ui));
+            if let Err(e)=p.save() {
+                w.modal_err(&ui,tr("Preferences"),&trf("Can't write {}: {}",&[&Prefs::path().display(),&e]));
+                return;
+            }
+            let _=cmd.send(Actions::Prefs(Box::new(p)));
+            w.hide(&ui);
+        }
+    });
+    cancel.on_clicked(ui, {
+        let ui=ui.clone();
+        let mut w=win.clone();
+        move |_| w.hide(&ui)
+    });
+
+    let mut vbox=VerticalBox::new(ui);
+    vbox.set_padded(ui,true);
+    vbox.append(ui,tabs,LayoutStrategy::Stretchy);
+    let mut bbox=HorizontalBox::new(ui);
+    bbox.set_padded(ui,true);
+    bbox.append(ui,Spacer::new(ui),LayoutStrategy::Stretchy);
+    bbox.append(ui,cancel,LayoutStrategy::Compact);
+    bbox.append(ui,save,LayoutStrategy::Compact);
+    vbox.append(ui,bbox,LayoutStrategy::Compact);
+    win.on_closing(ui, {
+        let ui=ui.clone();
+        move |w: &mut Window| w.hide(&ui)
+    });
+    win.set_margined(ui,true);
+    win.set_child(ui,vbox);
+    win.show(ui);
+}
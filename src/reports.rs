@@ -0,0 +1,107 @@
+use boros_core::spec::{Device,Generated,Localized};
+use boros_core::drift::{Drift,Limits};
+use boros_core::migrate::{Report,Rule};
+
+use crate::i18n::{tr,trf};
+
+// boros_core returns what it found as data; the texts shown for it are worded
+// here, in the language of the GUI.
+
+// Names the sections boros_core made up and titles devices that described
+// themselves. Can be called again, e.g. when the device is shown anew.
+pub fn localize(dev: &mut Device) {
+    if dev.described {
+        let title=if dev.sections.iter().all( |s| s.generated == Some(Generated::Parameters) ) {
+            trf("{} (generic editor)",&[&dev.signature])
+        } else {
+            trf("{} (described by the firmware)",&[&dev.signature])
+        };
+        dev.title=Localized::Plain(title);
+    }
+    for sec in &mut dev.sections {
+        let (name,help)=match sec.generated {
+            None => continue,
+            Some(Generated::General) => (tr("General"),""),
+            Some(Generated::Other) => (tr("Other"),tr("Parameters reported by the device but not described by its spec. They are edited as plain text.")),
+            Some(Generated::Parameters) => (tr("Parameters"),tr("This device is not in the spec. Every parameter is edited as text,\ncheck the firmware documentation for valid values.")),
+        };
+        sec.name=name.into();
+        sec.help=help.into();
+    }
+}
+
+// Values the spec or the firmware allows for an item
+pub fn limits(l: &Limits) -> String {
+    match l {
+        Limits::Int(vmin,vmax) => format!("{}..{}",vmin,vmax),
+        Limits::Hex(maxlen) => trf("up to {} bytes",&[&maxlen]),
+        Limits::Text(maxlen) => trf("up to {} characters",&[&maxlen]),
+        Limits::Choice(v) => trf("choices {}",&[&v.iter().map(i32::to_string).collect::<Vec<_>>().join(",")]),
+        Limits::Check => "0/1".into(),
+    }
+}
+
+// One line for the drift banner
+pub fn drift_summary(d: &Drift) -> String {
+    let mut parts=vec!();
+    if !d.unknown.is_empty() { parts.push(trf("{} field(s) not in the spec",&[&d.unknown.len()])) }
+    if !d.missing.is_empty() { parts.push(trf("{} spec field(s) not reported",&[&d.missing.len()])) }
+    if !d.captions.is_empty() { parts.push(trf("{} caption(s) differ",&[&d.captions.len()])) }
+    if !d.values.is_empty() { parts.push(trf("{} value(s) out of the spec limits",&[&d.values.len()])) }
+    if !d.ranges.is_empty() { parts.push(trf("{} limit(s) differ",&[&d.ranges.len()])) }
+    parts.join(", ")
+}
+
+// Drift report, one line per difference under a heading per kind
+pub fn drift_details(d: &Drift) -> String {
+    if d.is_empty() {
+        return tr("The firmware matches the spec.").into();
+    }
+    let mut r=vec!();
+    if !d.unknown.is_empty() {
+        r.push(tr("Reported by the device but missing from the spec (not editable):").to_string());
+        for e in &d.unknown { r.push(format!("  {} '{}' = {}",e.id,e.label,e.raw_value)) }
+    }
+    if !d.missing.is_empty() {
+        r.push(tr("In the spec but not reported by the device (shown with defaults):").to_string());
+        for (id,caption) in &d.missing { r.push(format!("  {} '{}'",id,caption)) }
+    }
+    if !d.captions.is_empty() {
+        r.push(tr("Captions that differ (spec / firmware):").to_string());
+        for (id,spec,fw) in &d.captions { r.push(format!("  {} '{}' / '{}'",id,spec,fw)) }
+    }
+    if !d.values.is_empty() {
+        r.push(tr("Values the spec does not accept (range or format changed?):").to_string());
+        for (id,raw,l) in &d.values { r.push(format!("  {} = {} ({})",id,raw,limits(l))) }
+    }
+    if !d.ranges.is_empty() {
+        r.push(tr("Limits that differ (spec / firmware):").to_string());
+        for (id,spec,fw) in &d.ranges { r.push(format!("  {} {} / {}",id,limits(spec),limits(fw))) }
+    }
+    r.join("\n")
+}
+
+// What a migration did to a configuration
+pub fn migration(r: &Report) -> String {
+    if r.is_empty() {
+        return tr("No changes were needed.").into();
+    }
+    let mut lines=vec!();
+    if !r.converted.is_empty() { lines.push(trf("Converted: {}",&[&r.converted.join(", ")])) }
+    if !r.dropped.is_empty() { lines.push(trf("Dropped: {}",&[&r.dropped.join(", ")])) }
+    lines.join("\n")
+}
+
+// Change a firmware upgrade makes to the configuration
+pub fn rule(r: &Rule) -> String {
+    match r {
+        Rule::Rename { from, to } => trf("'{}' is renamed to '{}'",&[&from,&to]),
+        Rule::Remove { id } => trf("'{}' is removed",&[&id]),
+        Rule::Map { id, values } => {
+            let m: Vec<String>=values.iter().map( |(a,b)| format!("{}->{}",a,b) ).collect();
+            trf("'{}' values change ({})",&[&id,&m.join(", ")])
+        },
+        Rule::Scale { id, factor, offset } => trf("'{}' is rescaled (x{} {})",&[&id,&factor,&format!("{:+}",offset)]),
+        Rule::Set { id, value } => trf("'{}' is set to {}",&[&id,&value]),
+    }
+}